@@ -0,0 +1,42 @@
+//! Parse latency for tiny utilities, from building the parser to reading the values back.
+//!
+//! Run with `cargo bench`.
+
+use rargsxd::*;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const RUNS: u32 = 20_000;
+
+fn tiny(args: Vec<String>) -> bool {
+    let mut parser = ArgParser::new("tiny");
+    parser.args(vec!(
+        Arg::new("quiet").short('q').help("Prints less").flag(false),
+        Arg::new("output").short('o').help("Where to write").option("-"),
+        Arg::new("count").short('n').help("How many").option("1"),
+    )).try_parse_vec(args).unwrap();
+    parser.get_flag("quiet").unwrap() && parser.get_option("output").unwrap() != "-"
+}
+
+fn bench(name: &str, args: &[&str]) {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    // Warm up so the first runs don't pay for page faults.
+    for _ in 0..RUNS / 10 {
+        black_box(tiny(black_box(args.clone())));
+    }
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        black_box(tiny(black_box(args.clone())));
+    }
+    let per_run: Duration = start.elapsed() / RUNS;
+    println!("{:<12} {:>8.2?} per parse", name, per_run);
+}
+
+fn main() {
+    bench("empty", &[]);
+    bench("long", &["--quiet", "--output", "out.txt", "--count", "3"]);
+    bench("cluster", &["-qo", "out.txt"]);
+    bench("extras", &["-q", "a", "b", "c", "--", "-d"]);
+}
@@ -1,4 +1,24 @@
-#[derive(Clone, PartialEq)]
+use super::error::{Error, ErrorKind};
+use super::parser::ArgParser;
+use super::value::{self, ValueType};
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Callback ran with the parser, see Arg::short_circuit.
+pub (crate) type ParserCallback = Arc<dyn Fn(&ArgParser) + Send + Sync>;
+/// Callback ran with an argument's value, see Arg::on_match.
+pub (crate) type MatchCallback = Arc<Mutex<dyn FnMut(&str) + Send>>;
+/// Check ran with an argument's value and the parser, see Arg::validator.
+pub (crate) type ValueValidator = Arc<dyn Fn(&str, &ArgParser) -> Result<(), String> + Send + Sync>;
+
+#[derive(Clone, PartialEq, Debug)]
 pub enum WordType {
     /// ArgType's flag but for words.
     Boolean(bool),
@@ -35,7 +55,7 @@ impl WordType {
 }
 
 /// Type of argument to check for.
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum ArgType {
     /// Only used for initialization. Will panic if there's any unknown ArgTypes when initializing.
     Unknown,
@@ -45,6 +65,9 @@ pub enum ArgType {
     Option_(String),
     /// ^^ those but without -/--
     Word(WordType),
+    /// Like Option_, but the value is the next argument exactly as it was given, even if it
+    /// starts with - or -- (`--grep-arg --include=*.rs`), without being interpreted or expanded.
+    Raw(String),
 }
 
 impl ArgType {
@@ -53,6 +76,11 @@ impl ArgType {
         Self::Option_(String::from(opt))
     }
 
+    /// Creates a new ArgType::Raw with `raw` &str as a default.
+    pub fn raw(raw: &str) -> Self {
+        Self::Raw(String::from(raw))
+    }
+
     /// Creates a new ArgType::Flag with `f` bool as a default.
     pub fn flag(f: bool) -> Self {
         Self::Flag(f)
@@ -64,20 +92,211 @@ impl ArgType {
     }
 }
 
+/// Kind of argument without its value, see Arg::get_kind.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ArgKind {
+    /// ArgType::Flag.
+    Flag,
+    /// ArgType::Option_ given by --name or -s.
+    Option,
+    /// ArgType::Raw given by --name or -s.
+    Raw,
+    /// ArgType::Word.
+    Word,
+    /// An option or raw option taking its value by position (Arg::positional).
+    Positional,
+    /// ArgType::Unknown, before the argument is given a type.
+    Unknown,
+}
+
+/// How many following values an option takes (Arg::nargs).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Nargs {
+    /// Exactly this many (`--size 800 600` for 2), none for an option that's only given or not.
+    Exactly(usize),
+    /// Every following value up to the next argument, possibly none (`*`).
+    ZeroOrMore,
+    /// Every following value up to the next argument, at least one (`+`).
+    OneOrMore,
+}
+
+impl Nargs {
+    /// Least and most values taken.
+    pub (crate) fn range(self) -> (usize, usize) {
+        match self {
+            Self::Exactly(n) => (n, n),
+            Self::ZeroOrMore => (0, usize::MAX),
+            Self::OneOrMore => (1, usize::MAX),
+        }
+    }
+}
+
+impl From<usize> for Nargs {
+    fn from(n: usize) -> Self {
+        Self::Exactly(n)
+    }
+}
+
+impl fmt::Display for Nargs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exactly(1) => write!(f, "1 value"),
+            Self::Exactly(n) => write!(f, "{} values", n),
+            Self::ZeroOrMore => write!(f, "any number of values"),
+            Self::OneOrMore => write!(f, "at least 1 value"),
+        }
+    }
+}
+
+/// What Arg::min_len and Arg::max_len count.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LengthUnit {
+    /// Characters (Unicode scalar values). This is the default.
+    Chars,
+    /// Bytes of UTF-8.
+    Bytes,
+}
+
+/// Where an argument's value came from, see ArgParser::dump.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ValueSource {
+    /// Not given, so it's the value it was defined with.
+    Default,
+    /// Given in the arguments parsed.
+    CommandLine,
+    /// Taken from its environment variable (Arg::env).
+    Environment,
+    /// Set by another argument (Arg::implies).
+    Implied,
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::CommandLine => write!(f, "command line"),
+            Self::Environment => write!(f, "environment"),
+            Self::Implied => write!(f, "implied"),
+        }
+    }
+}
+
+/// A time an argument was matched when parsing.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Occurrence {
+    /// Position of the first token in the arguments parsed.
+    pub index: usize,
+    /// Tokens that matched, as they were passed (`["-c", "auto"]`). Combined shorts share
+    /// their token (`-zxf FILE` gives `["-zxf"]` for -z and `["-zxf", "FILE"]` for -f).
+    pub tokens: Vec<String>,
+}
+
 /// An argument.
 #[derive(Clone)]
 pub struct Arg {
     /// Long name (-- if not ArgType::Word) to check for when parsing.
     pub (crate) name: String,
     /// Short name (- if not ArgType::Word) to check for when parsing.
-    pub (crate) short: char,
+    pub (crate) short: Option<char>,
+    /// Whether Arg::short was called, rather than the short being the name's first character.
+    pub (crate) short_given: bool,
     /// What's printed when self.print_help() is called.
-    pub (crate) help: String,
-    /// Type of argument to parse for.
-    pub (crate) typ: ArgType,
+    pub (crate) help: Cow<'static, str>,
+    /// Longer description printed instead of Arg::help by the expanded help (--help).
+    pub (crate) long_help: Option<Cow<'static, str>>,
+    /// Value given when parsing, over Arg::default (None until the argument is set, and put
+    /// back to None by ArgParser::reset).
+    pub (crate) parsed: Option<ArgType>,
+    /// Placeholder for the value in the help dialog and usage, the uppercased name if None.
+    pub (crate) value_name: Option<String>,
+    /// Leaves the argument out of the help dialog unless it's --help-all.
+    pub (crate) advanced: bool,
+    /// Leaves the default value out of the help dialog.
+    pub (crate) hide_default: bool,
+    /// Environment variable the value is taken from if the argument isn't passed.
+    pub (crate) env: Option<String>,
+    /// Name of the argument this one was renamed to (Arg::replaced_by).
+    pub (crate) replaced_by: Option<String>,
+    /// Operating systems or families the argument is registered on (any if empty).
+    pub (crate) platforms: Vec<String>,
+    /// Metadata for other tools (Arg::meta).
+    pub (crate) meta: BTreeMap<String, String>,
+    /// Type of argument to parse for, with the default it was defined with. Parsing leaves it
+    /// as it is, storing the value in Arg::parsed instead.
+    pub (crate) default: ArgType,
+
+    /// How the argument's value is interpreted when parsing.
+    pub (crate) value_type: ValueType,
+    /// Resolves ValueType::Path values to a canonical path when parsing.
+    pub (crate) canonicalize: bool,
+    /// Expands a leading ~ or ~user in ValueType::Path values when parsing.
+    pub (crate) expand_tilde: bool,
+    /// Expands $VAR and ${VAR} in the value when parsing.
+    pub (crate) expand_env: bool,
+    /// Expands ValueType::Path values that are glob patterns to the matching paths when parsing.
+    #[cfg(feature = "glob")]
+    pub (crate) glob: bool,
+    /// The canonicalized paths (if any) set when parsing.
+    pub (crate) paths: Vec<PathBuf>,
+    /// Number of values an ArgType::Option_ takes.
+    pub (crate) nargs: Nargs,
+    /// Collects every value given instead of keeping the last one (words take every
+    /// following value).
+    pub (crate) repeatable: bool,
+    /// Least number of values a repeatable argument takes if it's given.
+    pub (crate) min_values: usize,
+    /// Drops values given more than once, keeping the first.
+    pub (crate) dedup: bool,
+    /// Keeps the values sorted.
+    pub (crate) sorted: bool,
+    /// Sets the option to N when `-N` is passed (`-9` for level 9, like gzip).
+    pub (crate) digit_shorts: bool,
+    /// Takes its value from the arguments that aren't flags, options or words, in the order
+    /// positionals are given to the parser, instead of from --name.
+    pub (crate) positional: bool,
+    /// Takes every argument left once reached (or after `--`), dashes and all.
+    pub (crate) last: bool,
+    /// Reads the value from stdin when it's `-`.
+    pub (crate) stdin: bool,
+    /// `-x` turns the flag on and `+x` turns it off, instead of `-x` toggling it.
+    pub (crate) plus_toggle: bool,
+    /// Values the argument accepts (any if empty).
+    pub (crate) possible_values: Vec<String>,
+    /// Least length of each value, counted in Arg::len_unit.
+    pub (crate) min_len: Option<usize>,
+    /// Most length of each value, counted in Arg::len_unit.
+    pub (crate) max_len: Option<usize>,
+    /// What Arg::min_len and Arg::max_len count.
+    pub (crate) len_unit: LengthUnit,
+    /// Accepts empty values (`--name=`).
+    pub (crate) allow_empty: bool,
+    /// Every value given when parsing, in order.
+    pub (crate) values: Vec<String>,
+    /// Every value given when parsing as it was passed (before being lossily converted).
+    pub (crate) os_values: Vec<OsString>,
+    /// Every time the argument was matched when parsing, in order.
+    pub (crate) occurrences: Vec<Occurrence>,
+    /// (name, value) pairs of other arguments set when this argument is set.
+    pub (crate) implies: Vec<(String, String)>,
+    /// Names of arguments/groups that have to be passed with this argument.
+    pub (crate) requires: Vec<String>,
+    /// Names of arguments/groups that can't be passed with this argument.
+    pub (crate) conflicts: Vec<String>,
+    /// Also given to subcommands.
+    pub (crate) global: bool,
+    /// Position in the order arguments were given to the parser.
+    pub (crate) index: usize,
+    /// Ran (then exits) if the argument is passed, before checking required arguments.
+    pub (crate) short_circuit: Option<ParserCallback>,
+    /// Ran with the value every time the argument is set when parsing.
+    pub (crate) on_match: Option<MatchCallback>,
+    /// Checks ran with each value and the parser once everything is parsed.
+    pub (crate) validators: Vec<ValueValidator>,
 
     pub (crate) required: bool,
     pub (crate) set: bool,
+    /// Where the value came from.
+    pub (crate) source: ValueSource,
 }
 
 impl Arg {
@@ -86,41 +305,177 @@ impl Arg {
         let name = String::from(namee);
         Self {
             name,
-            short: namee.chars().nth(0).unwrap(),
-            help: String::new(),
-            typ: ArgType::Unknown,
+            short: namee.chars().next(),
+            short_given: false,
+            help: Cow::Borrowed(""),
+            long_help: None,
+            parsed: None,
+            value_name: None,
+            default: ArgType::Unknown,
+            hide_default: false,
+            advanced: false,
+            env: None,
+            platforms: Vec::new(),
+            meta: BTreeMap::new(),
+            replaced_by: None,
+            value_type: ValueType::String_,
+            canonicalize: false,
+            expand_tilde: false,
+            expand_env: false,
+            #[cfg(feature = "glob")]
+            glob: false,
+            paths: Vec::new(),
+            nargs: Nargs::Exactly(1),
+            repeatable: false,
+            min_values: 0,
+            dedup: false,
+            sorted: false,
+            digit_shorts: false,
+            positional: false,
+            last: false,
+            stdin: false,
+            plus_toggle: false,
+            possible_values: Vec::new(),
+            min_len: None,
+            max_len: None,
+            len_unit: LengthUnit::Chars,
+            allow_empty: true,
+            values: Vec::new(),
+            os_values: Vec::new(),
+            occurrences: Vec::new(),
+            implies: Vec::new(),
+            requires: Vec::new(),
+            conflicts: Vec::new(),
+            global: false,
+            index: 0,
+            short_circuit: None,
+            on_match: None,
+            validators: Vec::new(),
             required: false,
             set: false,
+            source: ValueSource::Default,
         }
     }
 
+    /// Creates a flag defaulting to false with `short` and `help`, the common case of Arg::new
+    /// with Arg::short, Arg::help and Arg::flag.
+    pub fn flag_new(name: &str, short: char, help: impl Into<Cow<'static, str>>) -> Self {
+        let mut arg = Self::new(name);
+        arg.short(short).help(help).flag(false);
+        arg
+    }
+
+    /// Creates an option without a default with `short`, `value_name` (Arg::value_name) and `help`.
+    pub fn option_new(name: &str, short: char, value_name: &str, help: impl Into<Cow<'static, str>>) -> Self {
+        let mut arg = Self::new(name);
+        arg.short(short).value_name(value_name).help(help).option("");
+        arg
+    }
+
+    /// Creates a word defaulting to `wt` with `help`.
+    pub fn word_new(name: &str, wt: WordType, help: impl Into<Cow<'static, str>>) -> Self {
+        let mut arg = Self::new(name);
+        arg.help(help).word(wt);
+        arg
+    }
+
     /// Makes the argument's type ArgType::Flag, giving it `val` bool.
     pub fn flag(&mut self, val: bool) -> &mut Self {
-        self.typ = ArgType::flag(val);
+        self.default = ArgType::flag(val);
+        self.parsed = None;
         self
     }
 
     /// Makes the argument's type ArgType::Option_, giving it `val` &str.
     pub fn option(&mut self, val: &str) -> &mut Self {
-        self.typ = ArgType::option(val);
+        self.default = ArgType::option(val);
+        self.parsed = None;
+        self
+    }
+
+    /// Makes the argument's type ArgType::Raw, giving it `val` &str.
+    pub fn raw(&mut self, val: &str) -> &mut Self {
+        self.default = ArgType::raw(val);
+        self.parsed = None;
         self
     }
 
     /// Makes the argument's type ArgType::Word, giving it `wt` WordType.
     pub fn word(&mut self, wt: WordType) -> &mut Self {
-        self.typ = ArgType::word(wt);
+        self.default = ArgType::word(wt);
+        self.parsed = None;
         self
     }
 
     /// Sets the output when the help menu is printed.
-    pub fn help(&mut self, help: &str) -> &mut Self {
-        self.help = String::from(help);
+    pub fn help(&mut self, help: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.help = help.into();
+        self
+    }
+
+    /// Sets the description shown by the expanded help (--help) instead of Arg::help, which
+    /// stays the one-liner shown by -h.
+    pub fn long_help(&mut self, help: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.long_help = Some(help.into());
+        self
+    }
+
+    /// Sets whether or not the argument is only listed by the complete help dialog (--help-all
+    /// or -hh), to keep -h and --help short for programs with lots of arguments.
+    pub fn advanced(&mut self, advanced: bool) -> &mut Self {
+        self.advanced = advanced;
+        self
+    }
+
+    /// Sets whether or not to leave `[default: ...]` out of the help dialog.
+    pub fn hide_default(&mut self, hide: bool) -> &mut Self {
+        self.hide_default = hide;
+        self
+    }
+
+    /// Sets the placeholder for the value in the help dialog and usage (`--out <FILE>`), which
+    /// is the uppercased name otherwise.
+    pub fn value_name(&mut self, name: &str) -> &mut Self {
+        self.value_name = Some(String::from(name));
+        self
+    }
+
+    /// Attaches `value` under `key` for tools reading the parser (docs, GUIs), like a category
+    /// or stability level. It does nothing when parsing and is shown by ArgParser::dump's JSON.
+    pub fn meta(&mut self, key: &str, value: &str) -> &mut Self {
+        self.meta.insert(String::from(key), String::from(value));
+        self
+    }
+
+    /// Sets whether or not empty values (`--name ""` or `--name=`) are accepted, which they are
+    /// by default.
+    pub fn allow_empty(&mut self, allow: bool) -> &mut Self {
+        self.allow_empty = allow;
+        self
+    }
+
+    /// Rejects values shorter than `len` (1 rejects empty values), counted in Arg::len_unit.
+    pub fn min_len(&mut self, len: usize) -> &mut Self {
+        self.min_len = Some(len);
+        self
+    }
+
+    /// Rejects values longer than `len`, counted in Arg::len_unit.
+    pub fn max_len(&mut self, len: usize) -> &mut Self {
+        self.max_len = Some(len);
+        self
+    }
+
+    /// Sets whether Arg::min_len and Arg::max_len count characters (the default) or bytes.
+    pub fn len_unit(&mut self, unit: LengthUnit) -> &mut Self {
+        self.len_unit = unit;
         self
     }
 
     /// Sets the argument's short name with `short` char.
     pub fn short(&mut self, short: char) -> &mut Self {
-        self.short = short;
+        self.short = Some(short);
+        self.short_given = true;
         self
     }
 
@@ -130,7 +485,694 @@ impl Arg {
         self
     }
 
+    /// Sets how the argument's value is interpreted with `vt` ValueType.
+    pub fn value_type(&mut self, vt: ValueType) -> &mut Self {
+        self.value_type = vt;
+        self
+    }
+
+    /// Sets whether or not to resolve the value to an absolute, canonicalized path when parsing.
+    /// Makes the argument's value type ValueType::Path.
+    pub fn canonicalize(&mut self, canonicalize: bool) -> &mut Self {
+        self.value_type = ValueType::Path;
+        self.canonicalize = canonicalize;
+        self
+    }
+
+    /// Sets whether or not to expand a leading `~` or `~user` in the value when parsing.
+    /// Makes the argument's value type ValueType::Path.
+    pub fn expand_tilde(&mut self, expand: bool) -> &mut Self {
+        self.value_type = ValueType::Path;
+        self.expand_tilde = expand;
+        self
+    }
+
+    /// Sets whether or not to expand glob patterns (`src/**/*.rs`) in the value to every matching
+    /// path when parsing, for shells that don't (Windows cmd). Patterns matching nothing are
+    /// kept as is. Makes the argument's value type ValueType::Path.
+    #[cfg(feature = "glob")]
+    pub fn glob(&mut self, glob: bool) -> &mut Self {
+        self.value_type = ValueType::Path;
+        self.glob = glob;
+        self
+    }
+
+    /// Takes the value from the `var` environment variable if the argument isn't passed (and
+    /// the variable isn't empty). Flags and boolean words accept the same spellings as
+    /// ValueType::Boolean.
+    pub fn env(&mut self, var: &str) -> &mut Self {
+        self.env = Some(String::from(var));
+        self
+    }
+
+    /// Sets whether or not to expand `$VAR` and `${VAR}` in the value using the process
+    /// environment when parsing.
+    pub fn expand_env(&mut self, expand: bool) -> &mut Self {
+        self.expand_env = expand;
+        self
+    }
+
+    /// Sets how many following values an option takes with `n` usize (`--size 800 600`).
+    pub fn num_values(&mut self, n: usize) -> &mut Self {
+        self.nargs = Nargs::Exactly(n);
+        self
+    }
+
+    /// Sets how many following values an option takes: Nargs::Exactly(n) (also given by `n`
+    /// usize), or every following value up to the next argument with Nargs::ZeroOrMore (`*`)
+    /// and Nargs::OneOrMore (`+`).
+    pub fn nargs(&mut self, nargs: impl Into<Nargs>) -> &mut Self {
+        self.nargs = nargs.into();
+        self
+    }
+
+    /// Sets whether or not an option takes a value at all. One that doesn't is only given or
+    /// not (see ArgParser::is_present).
+    pub fn takes_value(&mut self, takes_value: bool) -> &mut Self {
+        self.nargs = Nargs::Exactly(if takes_value {1} else {0});
+        self
+    }
+
+    /// Makes the argument collect every value given with `repeatable` bool: an option given
+    /// more than once keeps all of their values, and a WordType::String_ word takes every
+    /// following value (`prog lint a.rs b.rs`) up to the next argument.
+    pub fn repeatable(&mut self, repeatable: bool) -> &mut Self {
+        self.repeatable = repeatable;
+        self
+    }
+
+    /// Sets whether or not to drop values given more than once (`--tag a --tag a`), keeping
+    /// the first.
+    pub fn dedup(&mut self, dedup: bool) -> &mut Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Sets whether or not to sort the values (ArgParser::get_values), as strings.
+    pub fn sorted(&mut self, sorted: bool) -> &mut Self {
+        self.sorted = sorted;
+        self
+    }
+
+    /// Sets the least number of values an Arg::repeatable argument takes if it's given with
+    /// `n` usize.
+    pub fn min_values(&mut self, n: usize) -> &mut Self {
+        self.min_values = n;
+        self
+    }
+
+    /// Records the argument as an old name of the `name` argument instead of registering it, so
+    /// passing it fails with "'--old' was renamed to '--name'".
+    pub fn replaced_by(&mut self, name: &str) -> &mut Self {
+        self.replaced_by = Some(String::from(name));
+        self
+    }
+
+    /// Only registers the argument (so it's only parsed, shown in help and completed) on the
+    /// given `platforms`: operating systems or families as in std::env::consts::OS and
+    /// std::env::consts::FAMILY (`"linux"`, `"macos"`, `"windows"`, `"unix"`).
+    pub fn only_on<I, S>(&mut self, platforms: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.platforms.extend(platforms.into_iter().map(|platform| String::from(platform.as_ref())));
+        self
+    }
+
+    /// Sets whether or not `-N` (all digits, like gzip's `-1` to `-9`) sets the option to N.
+    ///
+    /// Values are taken first, so `--offset -5` with ParserSettings::allow_hyphen_values still
+    /// gives offset -5. Without an option using this, `-5` is short arguments (5).
+    pub fn digit_shorts(&mut self, digit_shorts: bool) -> &mut Self {
+        self.digit_shorts = digit_shorts;
+        self
+    }
+
+    /// Sets whether or not the option is positional: given by position (`prog in.txt out.txt`)
+    /// rather than by --name, in the order positionals are given to the parser.
+    ///
+    /// Required positionals can't come after optional ones (unless
+    /// ParserSettings::allow_missing_positional is set), and only the last one can be
+    /// Arg::repeatable (taking every positional value left). Parsing fails with an
+    /// ErrorKind::Definition error otherwise, checked before any argument is parsed rather than
+    /// here, since settings can still change until then.
+    pub fn positional(&mut self, positional: bool) -> &mut Self {
+        self.positional = positional;
+        self
+    }
+
+    /// Sets whether or not the positional takes every argument left once it's reached, or every
+    /// argument after `--`, including ones starting with - (`prog [options] -- CMD ARGS...`).
+    ///
+    /// It has to be the last positional.
+    pub fn last(&mut self, last: bool) -> &mut Self {
+        self.last = last;
+        self
+    }
+
+    /// Sets whether or not a value of `-` (`--password -`) is read from stdin instead, without
+    /// its trailing newline, to keep secrets and long texts out of the argument list. The bytes
+    /// read are kept as is for ArgParser::get_os_option.
+    pub fn stdin(&mut self, stdin: bool) -> &mut Self {
+        self.stdin = stdin;
+        self
+    }
+
+    /// Sets whether or not `-x` turns the flag on and `+x` turns it off (like `set -x` and
+    /// `set +x`), rather than `-x` toggling it. `+x` shorts can be combined (`+xv`).
+    pub fn plus_toggle(&mut self, plus_toggle: bool) -> &mut Self {
+        self.plus_toggle = plus_toggle;
+        self
+    }
+
+    /// Only accepts the `values` given when parsing (also offered by shell completions).
+    pub fn possible_values<I, S>(&mut self, values: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.possible_values.extend(values.into_iter().map(|value| String::from(value.as_ref())));
+        self
+    }
+
+    /// Sets the `name` argument to `val` when this argument is set, unless it was set itself.
+    /// Booleans (for flags and boolean words) accept the same spellings as ValueType::Boolean.
+    pub fn implies(&mut self, name: &str, val: &str) -> &mut Self {
+        self.implies.push((String::from(name), String::from(val)));
+        self
+    }
+
+    /// Requires the `name` argument (or any argument of the `name` ArgGroup) to be passed when
+    /// this argument is passed.
+    pub fn requires(&mut self, name: &str) -> &mut Self {
+        self.requires.push(String::from(name));
+        self
+    }
+
+    /// Prevents the `name` argument (or any argument of the `name` ArgGroup) from being passed
+    /// with this argument.
+    pub fn conflicts(&mut self, name: &str) -> &mut Self {
+        self.conflicts.push(String::from(name));
+        self
+    }
+
+    /// Sets whether or not the argument is also given to (and can be passed to) subcommands.
+    pub fn global(&mut self, global: bool) -> &mut Self {
+        self.global = global;
+        self
+    }
+
+    /// Runs `callback` with the parser and exits if the argument is passed, before required
+    /// arguments are checked (like --help), e.g. for --list-formats or --print-config. Parsing
+    /// returns instead with ParserSettings::return_on_info (ParseOutcome::ShortCircuited).
+    pub fn short_circuit<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: Fn(&ArgParser) + Send + Sync + 'static,
+    {
+        self.short_circuit = Some(Arc::new(callback));
+        self
+    }
+
+    /// Runs `callback` with the argument's value every time it's set when parsing (including
+    /// by Arg::implies), in order. Flags and boolean words give "true" or "false", and options
+    /// with Arg::nargs give each value.
+    pub fn on_match<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&str) + Send + 'static,
+    {
+        self.on_match = Some(Arc::new(Mutex::new(callback)));
+        self
+    }
+
+    /// Checks each of the argument's values with `validator` once every argument is parsed,
+    /// which can look at the others through the parser (`--max` against `--min`). An Err is
+    /// reported as an invalid value. Flags and boolean words give "true" or "false".
+    pub fn validator<F>(&mut self, validator: F) -> &mut Self
+    where
+        F: Fn(&str, &ArgParser) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.validators.push(Arc::new(validator));
+        self
+    }
+
     pub (crate) fn set(&mut self) {
         self.set = true;
+        // ArgParser::apply_env and ArgParser::apply_implications say otherwise afterwards.
+        if self.source == ValueSource::Default {
+            self.source = ValueSource::CommandLine;
+        }
+
+        if let ArgType::Flag(boolean) | ArgType::Word(WordType::Boolean(boolean)) = *self.current() {
+            self.run_on_match(&[boolean.to_string()]);
+        }
+    }
+
+    /// Runs the Arg::on_match callback with the values given by one occurrence.
+    fn run_on_match(&self, vals: &[String]) {
+        if let Some(callback) = &self.on_match {
+            let mut callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+            vals.iter().for_each(|val| callback(val));
+        }
+    }
+
+    /// Sets the argument's value (ArgType::Option_ or WordType::String_) to `val` after
+    /// interpreting it with the argument's value type.
+    pub (crate) fn set_value(&mut self, val: &str) -> Result<(), Error> {
+        self.set_values(&[val])
+    }
+
+    /// Sets the argument's values to `vals` after interpreting each with the argument's value
+    /// type. ArgType::Option_ and WordType::String_ are given the first value.
+    pub (crate) fn set_values(&mut self, vals: &[&str]) -> Result<(), Error> {
+        self.set_os_values(vals, &[])
+    }
+
+    /// Like Arg::set_values, but `raw` has the OsStrings `vals` were (lossily) converted from.
+    /// ValueType::String_ and ValueType::Path values that aren't valid UTF-8 are kept as is in
+    /// Arg::os_values (without being expanded).
+    pub (crate) fn set_os_values(&mut self, vals: &[&str], raw: &[OsString]) -> Result<(), Error> {
+        if self.stdin && vals.contains(&"-") {
+            let mut read = Vec::new();
+            let mut read_os = Vec::new();
+            for (idx, val) in vals.iter().enumerate() {
+                if *val == "-" {
+                    let (val, raw) = value::read_stdin().map_err(|e| self.invalid_value(val, &e))?;
+                    read.push(val);
+                    read_os.push(raw);
+                } else {
+                    read.push(String::from(*val));
+                    read_os.push(raw.get(idx).cloned().unwrap_or_else(|| OsString::from(val)));
+                }
+            }
+            let vals: Vec<&str> = read.iter().map(String::as_str).collect();
+            return self.store_os_values(&vals, &read_os);
+        }
+        self.store_os_values(vals, raw)
+    }
+
+    /// Arg::set_os_values once values from stdin have been read.
+    fn store_os_values(&mut self, vals: &[&str], raw: &[OsString]) -> Result<(), Error> {
+        if !self.allow_empty && vals.iter().any(|val| val.is_empty()) {
+            return Err(Error::new(ErrorKind::MissingValue,
+                &format!("A value is required for '{}' but none was supplied", self.display_name())));
+        }
+        if let ArgType::Raw(_) = self.default {
+            let os_values = vals.iter().enumerate()
+                .map(|(idx, val)| raw.get(idx).cloned().unwrap_or_else(|| OsString::from(val)));
+            let matched: Vec<String> = vals.iter().map(|val| String::from(*val)).collect();
+            if self.repeatable && self.set {
+                self.values.extend(vals.iter().map(|val| String::from(*val)));
+                self.os_values.extend(os_values);
+            } else {
+                self.values = vals.iter().map(|val| String::from(*val)).collect();
+                self.os_values = os_values.collect();
+            }
+            self.tidy_values();
+            self.parsed = Some(ArgType::Raw(self.values.first().cloned().unwrap_or_default()));
+            self.set();
+            self.run_on_match(&matched);
+            return Ok(());
+        }
+
+        let mut values = Vec::new();
+        let mut os_values = Vec::new();
+        let mut paths = Vec::new();
+        for (idx, val) in vals.iter().enumerate() {
+            let raw = raw.get(idx).filter(|raw| raw.to_str().is_none());
+            if let Some(raw) = raw {
+                if !matches!(self.value_type, ValueType::String_ | ValueType::Path) {
+                    return Err(Error::new(ErrorKind::InvalidUtf8,
+                        &format!("Invalid value {:?} for \"{}\": isn't valid UTF-8", raw, self.display_name())));
+                }
+
+                if !self.possible_values.is_empty() {
+                    return Err(self.invalid_value(val, &format!("expected one of {}", self.possible_values.join(", "))));
+                }
+
+                if self.value_type == ValueType::Path && self.canonicalize {
+                    paths.push(self.canonicalized(Path::new(raw))?);
+                }
+                values.push(String::from(*val));
+                os_values.push(raw.clone());
+                continue;
+            }
+
+            let mut val = String::from(*val);
+            if self.expand_env {
+                val = value::expand_env(&val);
+            }
+
+            if self.value_type == ValueType::Path && self.expand_tilde {
+                val = value::expand_tilde(&val);
+            }
+
+            #[cfg(feature = "glob")]
+            if self.value_type == ValueType::Path && self.glob {
+                let matches = value::expand_glob(&val).map_err(|e| self.invalid_value(&val, &e))?;
+                if !matches.is_empty() {
+                    for path in matches {
+                        if self.canonicalize {
+                            paths.push(self.canonicalized(&path)?);
+                        }
+                        values.push(path.to_string_lossy().into_owned());
+                        os_values.push(path.into_os_string());
+                    }
+                    continue;
+                }
+            }
+
+            if self.value_type == ValueType::Path && self.canonicalize {
+                paths.push(self.canonicalized(Path::new(&val))?);
+            }
+
+            value::validate(&self.value_type, &val).map_err(|e| self.invalid_value(&val, &e))?;
+            self.check_len(&val)?;
+            if !self.possible_values.is_empty() && !self.possible_values.contains(&val) {
+                return Err(self.invalid_value(&val, &format!("expected one of {}", self.possible_values.join(", "))));
+            }
+            os_values.push(OsString::from(&val));
+            values.push(val);
+        }
+
+        let matched = values.clone();
+        if self.repeatable && self.set {
+            self.values.extend(values);
+            self.os_values.extend(os_values);
+            self.paths.extend(paths);
+        } else {
+            self.values = values;
+            self.os_values = os_values;
+            self.paths = paths;
+        }
+        self.tidy_values();
+
+        let first = self.values.first().cloned().unwrap_or_default();
+        self.parsed = Some(match self.default {
+            ArgType::Word(_) => ArgType::Word(WordType::String_(first)),
+            _ => ArgType::Option_(first),
+        });
+        self.set();
+        self.run_on_match(&matched);
+        Ok(())
+    }
+
+    /// Checks `val` against Arg::min_len and Arg::max_len.
+    fn check_len(&self, val: &str) -> Result<(), Error> {
+        let (len, unit) = match self.len_unit {
+            LengthUnit::Chars => (val.chars().count(), "character"),
+            LengthUnit::Bytes => (val.len(), "byte"),
+        };
+        let plural = |n: usize| if n == 1 {""} else {"s"};
+        match (self.min_len, self.max_len) {
+            (Some(min), _) if len < min => Err(self.invalid_value(val, &format!("must be at least {} {}{} long", min, unit, plural(min)))),
+            (_, Some(max)) if len > max => Err(self.invalid_value(val, &format!("must be at most {} {}{} long", max, unit, plural(max)))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sorts and deduplicates the values as Arg::sorted and Arg::dedup say, keeping
+    /// Arg::os_values in step.
+    fn tidy_values(&mut self) {
+        if !self.sorted && !self.dedup {
+            return;
+        }
+
+        // Indexes of the values to keep, in order, applied to Arg::os_values and Arg::paths
+        // (canonicalized paths, one per value if there are any) too.
+        let mut order: Vec<usize> = (0..self.values.len()).collect();
+        if self.sorted {
+            order.sort_by(|a, b| self.values[*a].cmp(&self.values[*b]));
+        }
+        if self.dedup {
+            let mut seen = std::collections::BTreeSet::new();
+            order.retain(|idx| seen.insert(&self.values[*idx]));
+        }
+        self.values = order.iter().map(|idx| self.values[*idx].clone()).collect();
+        self.os_values = order.iter().filter_map(|idx| self.os_values.get(*idx).cloned()).collect();
+        if !self.paths.is_empty() {
+            self.paths = order.iter().filter_map(|idx| self.paths.get(*idx).cloned()).collect();
+        }
+    }
+
+    fn canonicalized(&self, path: &Path) -> Result<PathBuf, Error> {
+        value::canonicalize(path)
+            .map_err(|e| Error::new(ErrorKind::InvalidValue, &format!("{} for \"{}\"", e, self.display_name())))
+    }
+
+    /// Sets an ArgType::Flag to an explicitly given boolean `val` (--flag=yes).
+    pub (crate) fn set_flag(&mut self, val: &str) -> Result<(), Error> {
+        let boolean = value::parse_bool(val).map_err(|e| self.invalid_value(val, &e))?;
+        self.parsed = Some(ArgType::Flag(boolean));
+        self.set();
+        Ok(())
+    }
+
+    /// Whether or not the argument is registered on the platform it's running on (Arg::only_on).
+    pub (crate) fn on_this_platform(&self) -> bool {
+        self.platforms.is_empty() || self.platforms.iter().any(|platform| platform == env::consts::OS || platform == env::consts::FAMILY)
+    }
+
+    /// Forgets what parsing set, going back to the default it was defined with.
+    pub (crate) fn reset(&mut self) {
+        self.parsed = None;
+        self.values.clear();
+        self.os_values.clear();
+        self.paths.clear();
+        self.occurrences.clear();
+        self.set = false;
+        self.source = ValueSource::Default;
+    }
+
+    /// Records that `tokens` (starting at `index`) matched the argument.
+    pub (crate) fn occurred(&mut self, index: usize, tokens: &[String]) {
+        self.occurrences.push(Occurrence { index, tokens: tokens.to_vec() });
+    }
+
+    /// Checks that the argument can be matched when parsing.
+    pub (crate) fn validate(&self) -> Result<(), Error> {
+        let invalid = |why: &str| Err(Error::new(ErrorKind::Definition, &format!("Argument \"{}\" {}", self.name, why)));
+
+        if self.name.is_empty() {
+            return invalid("has an empty name");
+        } else if self.name.chars().any(char::is_whitespace) {
+            return invalid("has whitespace in its name");
+        } else if self.name.starts_with('-') {
+            return invalid("has a name starting with -, which is added when parsing");
+        } else if self.default == ArgType::Unknown {
+            return invalid("has type Unknown, it needs to be a flag, option or word");
+        } else if !matches!(self.default, ArgType::Word(_)) && self.name.contains('=') {
+            return invalid("has = in its name, which separates values when parsing");
+        } else if self.digit_shorts && !matches!(self.default, ArgType::Option_(_)) {
+            return invalid("uses digit_shorts, which only options can");
+        } else if self.positional && !matches!(self.default, ArgType::Option_(_) | ArgType::Raw(_)) {
+            return invalid("is positional, which only options can be");
+        } else if self.plus_toggle && !matches!(self.default, ArgType::Flag(_)) {
+            return invalid("uses plus_toggle, which only flags can");
+        } else if self.last && !self.positional {
+            return invalid("uses last, which only positionals can");
+        } else if self.positional && self.digit_shorts {
+            return invalid("is positional, so it can't use digit_shorts");
+        } else if self.short_given && !self.is_named() {
+            return invalid("has a short name, which only flags and options that aren't positional can");
+        }
+
+        match self.short {
+            Some('-') => invalid("has - as its short name"),
+            Some(short) if short.is_whitespace() => invalid("has whitespace as its short name"),
+            Some(short) if short.is_control() => invalid("has a control character as its short name"),
+            Some(short) if is_combining(short) => invalid("has a combining character as its short name"),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sets the argument to `val` as implied by another argument.
+    pub (crate) fn set_implied(&mut self, val: &str) -> Result<(), Error> {
+        match self.default {
+            ArgType::Flag(_) => self.set_flag(val),
+            ArgType::Word(WordType::Boolean(_)) => {
+                let boolean = value::parse_bool(val).map_err(|e| self.invalid_value(val, &e))?;
+                self.parsed = Some(ArgType::Word(WordType::Boolean(boolean)));
+                self.set();
+                Ok(())
+            },
+            _ => self.set_value(val),
+        }
+    }
+
+    pub (crate) fn invalid_value(&self, val: &str, error: &str) -> Error {
+        Error::new(ErrorKind::InvalidValue, &format!("Invalid value \"{}\" for \"{}\": {}", val, self.display_name(), error))
+    }
+
+    /// Name as the user would type it (--name for flags/options).
+    pub (crate) fn display_name(&self) -> String {
+        match self.default {
+            ArgType::Word(_) => self.name.clone(),
+            _ if self.positional => self.name.clone(),
+            _ => format!("--{}", self.name),
+        }
+    }
+
+    /// Placeholder for the value (Arg::value_name).
+    pub (crate) fn metavar(&self) -> String {
+        self.value_name.clone().unwrap_or_else(|| self.name.to_uppercase())
+    }
+
+    /// Gets the name the argument was made with.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the short name (without the -), if any. Words and positionals don't have one.
+    pub fn get_short(&self) -> Option<char> {
+        self.short
+    }
+
+    /// Gets the one-line help (Arg::help).
+    pub fn get_help(&self) -> &str {
+        &self.help
+    }
+
+    /// Gets the longer help shown by --help (Arg::long_help), if any.
+    pub fn get_long_help(&self) -> Option<&str> {
+        self.long_help.as_deref()
+    }
+
+    /// Gets the kind of argument, to branch on without matching (or cloning) Arg::get_type.
+    pub fn get_kind(&self) -> ArgKind {
+        match self.default {
+            ArgType::Option_(_) | ArgType::Raw(_) if self.positional => ArgKind::Positional,
+            ArgType::Flag(_) => ArgKind::Flag,
+            ArgType::Option_(_) => ArgKind::Option,
+            ArgType::Raw(_) => ArgKind::Raw,
+            ArgType::Word(_) => ArgKind::Word,
+            ArgType::Unknown => ArgKind::Unknown,
+        }
+    }
+
+    /// Gets the argument's type with its current value (the default until it's parsed).
+    pub fn get_type(&self) -> &ArgType {
+        self.current()
+    }
+
+    /// Gets the argument's type with the default it was defined with.
+    pub fn get_default(&self) -> &ArgType {
+        &self.default
+    }
+
+    /// Gets whether or not the argument has to be given (Arg::required).
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Gets whether or not the argument was given when parsing (or implied by one that was).
+    pub fn is_set(&self) -> bool {
+        self.set
+    }
+
+    /// Gets the metadata attached under `key` (Arg::meta), if any.
+    pub fn get_meta(&self, key: &str) -> Option<&str> {
+        self.meta.get(key).map(String::as_str)
+    }
+
+    /// Gets every key and value attached with Arg::meta, sorted by key.
+    pub fn get_all_meta(&self) -> &BTreeMap<String, String> {
+        &self.meta
+    }
+
+    /// Gets where the argument's value came from.
+    pub fn get_source(&self) -> ValueSource {
+        self.source
+    }
+
+    /// ArgType with the current value, Arg::parsed if the argument was set or Arg::default.
+    pub (crate) fn current(&self) -> &ArgType {
+        self.parsed.as_ref().unwrap_or(&self.default)
+    }
+
+    /// Whether or not the argument is given by --name or -s (flags and non-positional options).
+    pub (crate) fn is_named(&self) -> bool {
+        !matches!(self.default, ArgType::Word(_)) && !self.positional
+    }
+}
+
+/// Copies an Arg built inline with the &mut Self builders, for ArgParser::args.
+impl From<&mut Arg> for Arg {
+    fn from(arg: &mut Arg) -> Self {
+        arg.clone()
+    }
+}
+
+/// ArgBuilder state before a kind (flag, option or word) is given, which can't be registered.
+pub struct Untyped;
+
+/// ArgBuilder state once a kind is given, which ArgParser::typed_arg takes.
+pub struct Typed;
+
+/// Builds an Arg that has to be given a kind before ArgParser::typed_arg takes it, so an
+/// ArgType::Unknown argument is caught by the compiler instead of when parsing.
+///
+/// ```
+/// use rargsxd::*;
+///
+/// let mut parser = ArgParser::new("program");
+/// parser.typed_arg(ArgBuilder::new("verbose").flag(false).with(|arg| {arg.help("Prints more");}));
+/// ```
+pub struct ArgBuilder<K> {
+    arg: Arg,
+    kind: PhantomData<K>,
+}
+
+impl ArgBuilder<Untyped> {
+    /// Starts building an argument named `name`.
+    pub fn new(name: &str) -> Self {
+        Self { arg: Arg::new(name), kind: PhantomData }
     }
+
+    /// Makes it a flag defaulting to `val` (Arg::flag).
+    pub fn flag(mut self, val: bool) -> ArgBuilder<Typed> {
+        self.arg.flag(val);
+        ArgBuilder { arg: self.arg, kind: PhantomData }
+    }
+
+    /// Makes it an option defaulting to `val` (Arg::option).
+    pub fn option(mut self, val: &str) -> ArgBuilder<Typed> {
+        self.arg.option(val);
+        ArgBuilder { arg: self.arg, kind: PhantomData }
+    }
+
+    /// Makes it a raw option defaulting to `val` (Arg::raw).
+    pub fn raw(mut self, val: &str) -> ArgBuilder<Typed> {
+        self.arg.raw(val);
+        ArgBuilder { arg: self.arg, kind: PhantomData }
+    }
+
+    /// Makes it a word defaulting to `wt` (Arg::word).
+    pub fn word(mut self, wt: WordType) -> ArgBuilder<Typed> {
+        self.arg.word(wt);
+        ArgBuilder { arg: self.arg, kind: PhantomData }
+    }
+}
+
+impl<K> ArgBuilder<K> {
+    /// Sets anything else about the argument with the Arg builder methods.
+    pub fn with<F: FnOnce(&mut Arg)>(mut self, configure: F) -> Self {
+        configure(&mut self.arg);
+        self
+    }
+}
+
+impl ArgBuilder<Typed> {
+    /// Gets the built Arg.
+    pub fn build(self) -> Arg {
+        self.arg
+    }
+}
+
+/// Whether or not `ch` combines with the char before it (combining marks, zero width joiner and
+/// variation selectors), so it can't be typed as a short name on its own.
+pub (crate) fn is_combining(ch: char) -> bool {
+    matches!(ch as u32,
+        0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF | 0xFE20..=0xFE2F
+        | 0x200D | 0xFE00..=0xFE0F)
 }
@@ -0,0 +1,133 @@
+use super::argument::{Arg, ArgType};
+use super::parser::ArgParser;
+
+/// Shell to generate completions for with ArgParser::completions.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Generates a completion script for `parser` in `shell`.
+pub (crate) fn generate(parser: &ArgParser, shell: Shell) -> String {
+    let name = parser.get_name();
+    let args = parser.sorted_args();
+    let positionals: Vec<&str> = args.iter()
+        .filter(|arg| matches!(arg.default, ArgType::Word(_)))
+        .map(|arg| arg.name.as_str())
+        .collect();
+    // Subcommands are only completed as the first argument.
+    let subcommands = parser.subcommand_list();
+
+    match shell {
+        Shell::Bash => bash(name, &args, &positionals, &subcommands),
+        Shell::Zsh => zsh(name, &args, &positionals, &subcommands),
+        Shell::Fish => fish(name, &args, &positionals, &subcommands),
+    }
+}
+
+fn bash(name: &str, args: &[&Arg], positionals: &[&str], subcommands: &[(&str, &str)]) -> String {
+    let function = format!("_{}", name.replace(|ch: char| !ch.is_ascii_alphanumeric(), "_"));
+    let mut words: Vec<String> = Vec::new();
+    let mut values = String::new();
+    for arg in args.iter().filter(|arg| arg.is_named() && arg.default != ArgType::Unknown) {
+        let mut spellings = vec!(format!("--{}", arg.name));
+        spellings.extend(arg.short.map(|short| format!("-{}", short)));
+        words.extend(spellings.iter().cloned());
+
+        if matches!(arg.default, ArgType::Option_(_) | ArgType::Raw(_)) {
+            let reply = if arg.possible_values.is_empty() {
+                String::from("compgen -f -- \"$cur\"")
+            } else {
+                format!("compgen -W {} -- \"$cur\"", quote(&arg.possible_values.join(" ")))
+            };
+            values.push_str(&format!("        {}) COMPREPLY=($({})); return;;\n", spellings.join("|"), reply));
+        }
+    }
+    words.extend(positionals.iter().map(|word| String::from(*word)));
+
+    let mut first = String::new();
+    if !subcommands.is_empty() {
+        let first_words: Vec<&str> = words.iter().map(String::as_str).chain(subcommands.iter().map(|(name, _)| *name)).collect();
+        first = format!("    if [ \"$COMP_CWORD\" -eq 1 ]; then
+        COMPREPLY=($(compgen -W {} -- \"$cur\"))
+        return
+    fi
+", quote(&first_words.join(" ")));
+    }
+
+    format!("{function}() {{
+    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\" prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"
+    case \"$prev\" in
+{values}    esac
+{first}    COMPREPLY=($(compgen -W {words} -- \"$cur\"))
+}}
+complete -F {function} {name}
+", function = function, values = values, first = first, words = quote(&words.join(" ")), name = name)
+}
+
+fn zsh(name: &str, args: &[&Arg], positionals: &[&str], subcommands: &[(&str, &str)]) -> String {
+    let mut specs = Vec::new();
+    for arg in args.iter().filter(|arg| arg.is_named() && arg.default != ArgType::Unknown) {
+        let help = arg.help.replace('\\', "\\\\").replace('[', "\\[").replace(']', "\\]");
+        let value = match arg.default {
+            ArgType::Option_(_) | ArgType::Raw(_) if arg.possible_values.is_empty() => format!(":{}:_files", arg.name),
+            ArgType::Option_(_) | ArgType::Raw(_) => format!(":{}:({})", arg.name, arg.possible_values.join(" ")),
+            _ => String::new(),
+        };
+
+        specs.push(quote(&format!("--{}[{}]{}", arg.name, help, value)));
+        if let Some(short) = arg.short {
+            specs.push(quote(&format!("-{}[{}]{}", short, help, value)));
+        }
+    }
+
+    if !subcommands.is_empty() {
+        let described: Vec<String> = subcommands.iter()
+            .map(|(name, about)| format!("{}\\:{}", name, about.replace(' ', "\\ ")))
+            .collect();
+        specs.push(quote(&format!("1: :(({}))", described.join(" "))));
+    }
+    if !positionals.is_empty() {
+        specs.push(quote(&format!("*:: :({})", positionals.join(" "))));
+    }
+    format!("#compdef {}\n\n_arguments \\\n    {}\n", name, specs.join(" \\\n    "))
+}
+
+fn fish(name: &str, args: &[&Arg], positionals: &[&str], subcommands: &[(&str, &str)]) -> String {
+    let mut script = String::new();
+    for arg in args.iter().filter(|arg| arg.is_named() && arg.default != ArgType::Unknown) {
+        script.push_str(&format!("complete -c {} -l {}", name, arg.name));
+        if let Some(short) = arg.short {
+            script.push_str(&format!(" -s {}", short));
+        }
+        if !arg.help.is_empty() {
+            script.push_str(&format!(" -d {}", quote(&arg.help)));
+        }
+        if matches!(arg.default, ArgType::Option_(_) | ArgType::Raw(_)) {
+            script.push_str(" -r");
+            if !arg.possible_values.is_empty() {
+                script.push_str(&format!(" -f -a {}", quote(&arg.possible_values.join(" "))));
+            }
+        }
+        script.push('\n');
+    }
+
+    for (sub, about) in subcommands {
+        script.push_str(&format!("complete -c {} -f -n __fish_use_subcommand -a {}", name, quote(sub)));
+        if !about.is_empty() {
+            script.push_str(&format!(" -d {}", quote(about)));
+        }
+        script.push('\n');
+    }
+    if !positionals.is_empty() {
+        script.push_str(&format!("complete -c {} -f -a {}\n", name, quote(&positionals.join(" "))));
+    }
+    script
+}
+
+/// Single quotes `s` for the shell.
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
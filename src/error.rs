@@ -0,0 +1,59 @@
+use std::fmt;
+
+/// Kind of error that made parsing fail.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ErrorKind {
+    /// An argument that isn't registered (`--bogus`).
+    UnknownArgument,
+    /// An option or word that wasn't given (enough) values.
+    MissingValue,
+    /// A value that couldn't be interpreted with the argument's ValueType.
+    InvalidValue,
+    /// A value that isn't valid UTF-8 passed to an argument whose ValueType needs it.
+    InvalidUtf8,
+    /// A required argument or group that wasn't passed.
+    MissingRequired,
+    /// Arguments passed that can't be used together.
+    Conflict,
+    /// An abbreviated long argument that matches more than one argument.
+    Ambiguous,
+    /// The parser or its arguments were set up wrong (e.g. an Arg with ArgType::Unknown).
+    Definition,
+    /// Returned by a hook (ArgParser::before_parse, ArgParser::check, ArgParser::after_parse).
+    Custom,
+}
+
+/// Error returned when parsing fails.
+#[derive(Clone, Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    message: String,
+}
+
+impl Error {
+    /// Creates a new Error of `kind` ErrorKind with `message` &str.
+    pub fn new(kind: ErrorKind, message: &str) -> Self {
+        Self {
+            kind,
+            message: String::from(message),
+        }
+    }
+
+    /// Gets the kind of error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Gets the message printed for the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,58 @@
+/// A named group of arguments.
+///
+/// Groups can be used in Arg::requires and Arg::conflicts in place of an argument's name, in
+/// which case any of the group's arguments satisfies (or conflicts with) the constraint.
+#[derive(Clone)]
+pub struct ArgGroup {
+    /// Name used to refer to the group.
+    pub (crate) name: String,
+    /// Names of the arguments in the group.
+    pub (crate) args: Vec<String>,
+    /// Prints help/exits if none of the group's arguments are passed.
+    pub (crate) required: bool,
+    /// Whether or not more than one of the group's arguments can be passed.
+    pub (crate) multiple: bool,
+    /// Position in the order groups were given to the parser.
+    pub (crate) index: usize,
+}
+
+impl ArgGroup {
+    /// Creates a new group with `name` &str.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: String::from(name),
+            args: Vec::new(),
+            required: false,
+            multiple: true,
+            index: 0,
+        }
+    }
+
+    /// Adds the arguments named in `args` to the group.
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.args.extend(args.into_iter().map(|arg| String::from(arg.as_ref())));
+        self
+    }
+
+    /// Adds the argument named `arg` to the group.
+    pub fn arg(&mut self, arg: &str) -> &mut Self {
+        self.args.push(String::from(arg));
+        self
+    }
+
+    /// Sets whether or not to print help/exit if none of the group's arguments are passed.
+    pub fn required(&mut self, required: bool) -> &mut Self {
+        self.required = required;
+        self
+    }
+
+    /// Sets whether or not more than one of the group's arguments can be passed.
+    pub fn multiple(&mut self, multiple: bool) -> &mut Self {
+        self.multiple = multiple;
+        self
+    }
+}
@@ -0,0 +1,298 @@
+use std::fmt;
+
+/// What the help dialog shows, for rendering it yourself (ArgParser::get_help).
+///
+/// Displaying it gives what ArgParser::print_help prints.
+#[derive(Clone, Debug)]
+pub struct Help {
+    pub name: String,
+    pub version: String,
+    pub author: String,
+    pub info: String,
+    pub copyright: String,
+    /// SPDX license expression (ArgParser::license), empty if not set.
+    pub license: String,
+    /// Homepage URL (ArgParser::homepage), empty if not set.
+    pub homepage: String,
+    /// Where to report bugs (ArgParser::bug_report_url), shown last.
+    pub bug_report_url: Option<String>,
+    pub usage: String,
+    /// Non-empty sections ("Arguments", "Flags", "Options", "Words", "Commands" unless
+    /// ArgParser::group_help and ArgParser::section_title say otherwise) in the order they're printed.
+    pub sections: Vec<HelpSection>,
+    /// Example commands (ArgParser::example), listed after the sections.
+    pub examples: Vec<String>,
+    /// Displays HelpEntry::long_help instead of HelpEntry::help (--help rather than -h).
+    pub expanded: bool,
+    /// Columns lines are wrapped at (before the tags after the help text).
+    pub width: usize,
+}
+
+/// A titled list of arguments in the help dialog.
+#[derive(Clone, Debug)]
+pub struct HelpSection {
+    pub title: String,
+    pub entries: Vec<HelpEntry>,
+}
+
+/// An argument in the help dialog.
+#[derive(Clone, Default, Debug)]
+pub struct HelpEntry {
+    /// Name the argument was registered with.
+    pub name: String,
+    /// Short name (without the -), if any.
+    pub short: Option<char>,
+    /// Long name (without the --), None for words.
+    pub long: Option<String>,
+    /// Placeholder for the value, None if the argument doesn't take one.
+    pub metavar: Option<String>,
+    pub help: String,
+    /// Longer description shown by the expanded help (Arg::long_help), if any.
+    pub long_help: Option<String>,
+    /// Default value, None for flags, boolean words, empty defaults and Arg::hide_default.
+    pub default: Option<String>,
+    /// Environment variable the value can be given with (Arg::env), if any.
+    pub env: Option<String>,
+    /// Values accepted (Arg::possible_values), any if empty.
+    pub possible_values: Vec<String>,
+}
+
+impl fmt::Display for Help {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}\n{}\n{}\n{}", self.name, self.version, self.author, self.info, self.copyright)?;
+        writeln!(f, "\nUsage:\n\t{}", self.usage)?;
+
+        // Descriptions line up in one column across every section.
+        let width = self.sections.iter()
+            .flat_map(|section| &section.entries)
+            .map(|entry| display_width(&cell(entry)))
+            .max()
+            .unwrap_or(0);
+        let start = 8 + width + 2;
+        let indent = format!("\n\t{}", " ".repeat(width + 2));
+
+        for section in &self.sections {
+            writeln!(f, "\n{}:", section.title)?;
+            for entry in &section.entries {
+                let help = match &entry.long_help {
+                    Some(long_help) if self.expanded => long_help,
+                    _ => &entry.help,
+                };
+                let mut column = start + display_width(help.lines().last().unwrap_or(""));
+                let mut help = help.replace('\n', &indent);
+
+                for tag in tags(entry) {
+                    if !help.is_empty() && column + 1 + display_width(&tag) > self.width {
+                        help.push_str(&indent);
+                        column = start;
+                    } else if !help.is_empty() {
+                        help.push(' ');
+                        column += 1;
+                    }
+                    column += display_width(&tag);
+                    help.push_str(&tag);
+                }
+
+                let cell = cell(entry);
+                let padding = " ".repeat(width - display_width(&cell) + 2);
+                writeln!(f, "\t{}", format!("{}{}{}", cell, padding, help).trim_end())?;
+            }
+        }
+
+        if !self.examples.is_empty() {
+            writeln!(f, "\nExamples:")?;
+            for example in &self.examples {
+                writeln!(f, "\t{}", example)?;
+            }
+        }
+        if let Some(url) = &self.bug_report_url {
+            writeln!(f, "\nReport bugs at <{}>", url)?;
+        }
+        Ok(())
+    }
+}
+
+impl Help {
+    /// Renders the help as markdown, with HelpEntry::long_help where there is one.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n", format!("{} {}", self.name, self.version).trim_end());
+        if !self.info.is_empty() {
+            out.push_str(&format!("{}\n\n", self.info));
+        }
+        out.push_str(&format!("## Usage\n\n```\n{}\n```\n", self.usage));
+
+        for section in &self.sections {
+            out.push_str(&format!("\n## {}\n\n", section.title));
+            for entry in &section.entries {
+                let mut line = format!("- `{}`", spelling(entry));
+                let description = description(entry);
+                if !description.is_empty() {
+                    line.push_str(&format!(": {}", description.replace('\n', "\n  ")));
+                }
+                for tag in tags(entry) {
+                    line.push_str(&format!(" {}", tag));
+                }
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+
+        if !self.examples.is_empty() {
+            out.push_str(&format!("\n## Examples\n\n```\n{}\n```\n", self.examples.join("\n")));
+        }
+        if !self.author.is_empty() {
+            out.push_str(&format!("\n## Author\n\n{}\n", self.author));
+        }
+        if !self.copyright.is_empty() {
+            out.push_str(&format!("\n## Copyright\n\n{}\n", self.copyright));
+        }
+        if !self.license.is_empty() {
+            out.push_str(&format!("\n## License\n\n{}\n", self.license));
+        }
+        if !self.homepage.is_empty() {
+            out.push_str(&format!("\n## Homepage\n\n<{}>\n", self.homepage));
+        }
+        out
+    }
+
+    /// Renders the help as a man page (roff), with HelpEntry::long_help where there is one.
+    pub fn to_man(&self) -> String {
+        let mut out = format!(".TH {} 1 \"\" \"{}\"\n", roff(&self.name.to_uppercase()), roff(format!("{} {}", self.name, self.version).trim_end()));
+        out.push_str(&format!(".SH NAME\n{}", roff(&self.name)));
+        if let Some(first) = self.info.lines().next() {
+            out.push_str(&format!(" \\- {}", roff(first)));
+        }
+        out.push_str(&format!("\n.SH SYNOPSIS\n{}\n", roff(&self.usage)));
+        if !self.info.is_empty() {
+            out.push_str(&format!(".SH DESCRIPTION\n{}\n", roff(&self.info)));
+        }
+
+        for section in &self.sections {
+            out.push_str(&format!(".SH {}\n", section.title.to_uppercase()));
+            for entry in &section.entries {
+                out.push_str(&format!(".TP\n\\fB{}\\fR\n", roff(&spelling(entry))));
+                let mut description = description(entry).to_string();
+                for tag in tags(entry) {
+                    if !description.is_empty() {
+                        description.push(' ');
+                    }
+                    description.push_str(&tag);
+                }
+                if !description.is_empty() {
+                    out.push_str(&roff(&description));
+                    out.push('\n');
+                }
+            }
+        }
+
+        if !self.examples.is_empty() {
+            out.push_str(".SH EXAMPLES\n");
+            for example in &self.examples {
+                out.push_str(&format!(".PP\n{}\n", roff(example)));
+            }
+        }
+        if !self.author.is_empty() {
+            out.push_str(&format!(".SH AUTHOR\n{}\n", roff(&self.author)));
+        }
+        let mut copyright = Vec::new();
+        if !self.copyright.is_empty() {
+            copyright.push(roff(&self.copyright));
+        }
+        if !self.license.is_empty() {
+            copyright.push(roff(&format!("License: {}", self.license)));
+        }
+        if !self.homepage.is_empty() {
+            copyright.push(roff(&format!("Homepage: {}", self.homepage)));
+        }
+        if !copyright.is_empty() {
+            out.push_str(&format!(".SH COPYRIGHT\n{}\n", copyright.join("\n.br\n")));
+        }
+        out
+    }
+}
+
+/// "-s, --long <METAVAR>", "--long", or the name for words.
+fn spelling(entry: &HelpEntry) -> String {
+    let mut spelling = match (&entry.long, entry.short) {
+        (Some(long), Some(short)) => format!("-{}, --{}", short, long),
+        (Some(long), None) => format!("--{}", long),
+        (None, _) => entry.name.clone(),
+    };
+    if let Some(metavar) = &entry.metavar {
+        spelling.push_str(&format!(" <{}>", metavar));
+    }
+    spelling
+}
+
+/// The long help if there is one, otherwise the one-line help.
+fn description(entry: &HelpEntry) -> &str {
+    entry.long_help.as_deref().unwrap_or(&entry.help)
+}
+
+/// "[default: x]", "[env: X]" and "[possible values: a, b]", for those the entry has.
+fn tags(entry: &HelpEntry) -> Vec<String> {
+    let possible_values = Some(&entry.possible_values).filter(|values| !values.is_empty());
+    entry.default.iter().map(|default| format!("[default: {}]", default))
+        .chain(entry.env.iter().map(|env| format!("[env: {}]", env)))
+        .chain(possible_values.map(|values| format!("[possible values: {}]", values.join(", "))))
+        .collect()
+}
+
+/// Escapes text for roff, so dashes, backslashes and leading dots come out literally.
+fn roff(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line = line.replace('\\', "\\e").replace('-', "\\-");
+            if line.starts_with('.') || line.starts_with('\'') {
+                format!("\\&{}", line)
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// "-s, --long <METAVAR>" for the help dialog, with the short padded out so longs line up.
+fn cell(entry: &HelpEntry) -> String {
+    match &entry.long {
+        Some(long) => {
+            let mut cell = format!("{}--{}", short_prefix(entry.short), long);
+            if let Some(metavar) = &entry.metavar {
+                cell.push_str(&format!(" <{}>", metavar));
+            }
+            cell
+        },
+        None => entry.name.clone(),
+    }
+}
+
+/// Columns `text` takes up in a terminal, so wide (CJK) and combining characters still line up.
+#[cfg(feature = "unicode-width")]
+fn display_width(text: &str) -> usize {
+    unicode_width::UnicodeWidthStr::width(text)
+}
+
+/// Columns `text` takes up in a terminal, so wide (CJK) and combining characters still line up.
+///
+/// Covers the common ranges; the unicode-width feature uses the full tables.
+#[cfg(not(feature = "unicode-width"))]
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x200B..=0x200F | 0x20D0..=0x20FF | 0xFE00..=0xFE0F | 0xFE20..=0xFE2F => 0,
+            0x1100..=0x115F | 0x2E80..=0x303E | 0x3041..=0x33FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xA000..=0xA4CF
+                | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF | 0xFE30..=0xFE4F | 0xFF00..=0xFF60 | 0xFFE0..=0xFFE6
+                | 0x1F300..=0x1F64F | 0x1F900..=0x1F9FF | 0x20000..=0x3FFFD => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// "-s, " for the help dialog, or padding if there's no short name.
+fn short_prefix(short: Option<char>) -> String {
+    match short {
+        Some(short) => format!("-{}, ", short),
+        None => String::from("    "),
+    }
+}
@@ -30,13 +30,38 @@
 //! assert!(parser.get_word("testword").unwrap().as_bool().unwrap());
 //! assert_eq!(parser.get_option("testoption").unwrap(), "monke");
 //! ```
+//!
+//! # Determinism
+//!
+//! Parsing the same arguments with the same parser always gives the same values, the same error
+//! and the same help dialog, byte for byte, however many times it's parsed (rargsxd::parse,
+//! ArgParser::reset). Arguments and groups are kept in ordered maps and checked in the order
+//! they were given to the parser (see ArgParser::try_parse_vec), and nothing depends on hashing,
+//! so only the environment (Arg::env, `$COLUMNS` for the help width...) and callbacks can make
+//! runs differ. Programs can snapshot test their arguments with this (see the testing module).
 
 // Copyright (C) 2021 BubbyRoosh
 mod argument;
+mod completions;
+mod error;
+mod group;
+mod help;
+mod macros;
 mod parser;
+mod quote;
+mod settings;
+pub mod testing;
+mod value;
 
 pub use argument::*;
+pub use completions::Shell;
+pub use error::*;
+pub use group::*;
+pub use help::*;
 pub use parser::*;
+pub use quote::*;
+pub use settings::*;
+pub use value::*;
 
 
 #[cfg(test)]
@@ -118,4 +143,2399 @@ mod tests {
         assert_eq!(parser.get_option("monke").unwrap(), "oo oo");
         assert!(parser.extra.contains(&String::from("extra")));
     }
+
+    #[test]
+    fn canonicalize_path() {
+        let args = vec!(
+            "--dir".to_string(),
+            "src/../src".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("dir")
+                        .option(".")
+                        .canonicalize(true),
+                )
+            ).parse_vec(args);
+
+        assert_eq!(parser.get_path("dir").unwrap(), std::fs::canonicalize("src").unwrap());
+        assert!(parser.get_path("dir").unwrap().is_absolute());
+    }
+
+    #[test]
+    fn tilde_expansion() {
+        let args = vec!(
+            "--config=~/file".to_string(),
+            "--other".to_string(),
+            "~/file".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("config")
+                        .option("")
+                        .expand_tilde(true),
+                    Arg::new("other")
+                        .option(""),
+                )
+            ).parse_vec(args);
+
+        let home = std::env::var("HOME").unwrap();
+        assert_eq!(parser.get_option("config").unwrap(), format!("{}/file", home.trim_end_matches('/')));
+        assert_eq!(parser.get_option("other").unwrap(), "~/file");
+    }
+
+    #[test]
+    fn env_expansion() {
+        let args = vec!(
+            "--path".to_string(),
+            "$CARGO_PKG_NAME/${CARGO_PKG_NAME}_$RARGSXD_UNSET_VAR$".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("path")
+                        .option("")
+                        .expand_env(true),
+                )
+            ).parse_vec(args);
+
+        assert_eq!(parser.get_option("path").unwrap(), "rargsxd/rargsxd_$");
+    }
+
+    #[test]
+    fn numeric_literals() {
+        let args = vec!(
+            "--mode".to_string(), "0o755".to_string(),
+            "--addr=0xFFFF_FFFF_FFFF_FFFF".to_string(),
+            "--count".to_string(), "1_000_000".to_string(),
+            "--mask=0b1010".to_string(),
+            "--offset=-0x1F".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("mode").option("0").value_type(ValueType::Unsigned),
+                    Arg::new("addr").option("0").value_type(ValueType::Unsigned),
+                    Arg::new("count").option("0").value_type(ValueType::Integer),
+                    Arg::new("mask").option("0").value_type(ValueType::Integer),
+                    Arg::new("offset").option("0").value_type(ValueType::Integer),
+                )
+            ).parse_vec(args);
+
+        assert_eq!(parser.get_uint("mode").unwrap(), 0o755);
+        assert_eq!(parser.get_uint("addr").unwrap(), u64::MAX);
+        assert_eq!(parser.get_int("count").unwrap(), 1_000_000);
+        assert_eq!(parser.get_int("mask").unwrap(), 10);
+        assert_eq!(parser.get_int("offset").unwrap(), -31);
+
+        assert!(value::parse_int("0x8000_0000_0000_0000").is_err());
+        assert_eq!(value::parse_int("-0x8000_0000_0000_0000").unwrap(), i64::MIN);
+        assert!(value::parse_uint("0x1_0000_0000_0000_0000").is_err());
+        assert!(value::parse_int("1__0").is_err());
+        assert!(value::parse_int("0x").is_err());
+    }
+
+    #[test]
+    fn liberal_booleans() {
+        let args = vec!(
+            "--verbose=Yes".to_string(),
+            "--quiet=off".to_string(),
+            "--color".to_string(), "ON".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("verbose").flag(false),
+                    Arg::new("quiet").flag(true),
+                    Arg::new("color").option("no").value_type(ValueType::Boolean),
+                )
+            ).parse_vec(args);
+
+        assert!(parser.get_flag("verbose").unwrap());
+        assert!(!parser.get_flag("quiet").unwrap());
+        assert!(parser.get_bool("color").unwrap());
+        assert!(value::parse_bool("maybe").unwrap_err().contains("yes, no"));
+    }
+
+    #[test]
+    fn percentages() {
+        let args = vec!(
+            "--sample=75%".to_string(),
+            "--opacity".to_string(), "0.5".to_string(),
+            "--load=3/4".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("sample").option("100%").value_type(ValueType::Percent),
+                    Arg::new("opacity").option("1").value_type(ValueType::Percent),
+                    Arg::new("load").option("1").value_type(ValueType::Percent),
+                )
+            ).parse_vec(args);
+
+        assert_eq!(parser.get_percent("sample").unwrap(), 0.75);
+        assert_eq!(parser.get_percent("opacity").unwrap(), 0.5);
+        assert_eq!(parser.get_percent("load").unwrap(), 0.75);
+        assert!(value::parse_percent("150%").is_err());
+        assert!(value::parse_percent("1/0").is_err());
+        assert!(value::parse_percent("half").is_err());
+    }
+
+    #[test]
+    fn colors() {
+        let args = vec!(
+            "--fg=#FF8800".to_string(),
+            "--bg".to_string(), "#08f".to_string(),
+            "--border=Navy".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("fg").option("white").value_type(ValueType::Color),
+                    Arg::new("bg").option("black").value_type(ValueType::Color),
+                    Arg::new("border").option("gray").value_type(ValueType::Color),
+                )
+            ).parse_vec(args);
+
+        assert_eq!(parser.get_color("fg").unwrap(), (255, 136, 0));
+        assert_eq!(parser.get_color("bg").unwrap(), (0, 136, 255));
+        assert_eq!(parser.get_color("border").unwrap(), (0, 0, 128));
+        assert!(value::parse_color("#12345").is_err());
+        assert!(value::parse_color("#ééé").is_err());
+        assert!(value::parse_color("chartreuse-ish").is_err());
+    }
+
+    #[test]
+    fn typed_values() {
+        #[derive(Debug, PartialEq)]
+        struct Id(u32);
+
+        impl FromArgValue for Id {
+            fn from_arg_value(val: &str) -> Result<Self, String> {
+                val.strip_prefix("id-")
+                    .and_then(|n| n.parse().ok())
+                    .map(Id)
+                    .ok_or_else(|| String::from("expected id-N"))
+            }
+        }
+
+        let args = vec!(
+            "--id=id-42".to_string(),
+            "--rate".to_string(), "2.5".to_string(),
+            "name".to_string(), "monke".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("id").option(""),
+                    Arg::new("rate").option("1"),
+                    Arg::new("name").word(WordType::string("")),
+                )
+            ).parse_vec(args);
+
+        assert_eq!(parser.get_typed::<Id>("id").unwrap(), Id(42));
+        assert_eq!(parser.get_typed::<f64>("rate").unwrap(), 2.5);
+        assert_eq!(parser.get_typed::<String>("name").unwrap(), "monke");
+        assert!(parser.get_typed::<u8>("rate").is_none());
+        assert!(parser.get_typed::<u8>("help").is_none());
+    }
+
+    #[test]
+    fn num_values() {
+        let args = vec!(
+            "--size".to_string(), "800".to_string(), "600".to_string(),
+            "-r".to_string(), "1".to_string(), "5".to_string(),
+            "--pair=key".to_string(), "value".to_string(),
+            "extra".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("size").option("").num_values(2),
+                    Arg::new("range").option("").num_values(2),
+                    Arg::new("pair").option("").num_values(2),
+                    Arg::new("unused").option("default").num_values(2),
+                )
+            ).parse_vec(args);
+
+        assert_eq!(parser.get_typed_values::<u32>("size").unwrap(), vec!(800, 600));
+        assert_eq!(parser.get_values("range").unwrap(), vec!("1", "5"));
+        assert_eq!(parser.get_values("pair").unwrap(), vec!("key", "value"));
+        assert_eq!(parser.get_values("unused").unwrap(), vec!("default"));
+        assert_eq!(parser.extra, vec!("extra"));
+    }
+
+    #[test]
+    fn nargs() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("files").option("").nargs(Nargs::OneOrMore),
+                    Arg::new("tags").option("").nargs(Nargs::ZeroOrMore),
+                    Arg::new("dry").option("").takes_value(false),
+                    Arg::new("size").option("").nargs(2),
+                )
+            ).parse_vec(vec!(
+                "--files".to_string(), "a".to_string(), "b".to_string(),
+                "--tags".to_string(),
+                "--dry".to_string(), "rest".to_string(),
+            ));
+
+        assert_eq!(parser.get_values("files").unwrap(), vec!("a", "b"));
+        assert!(parser.is_present("tags"));
+        assert!(parser.is_present("dry"));
+        assert_eq!(parser.extra, vec!("rest"));
+
+        let error = parser.try_parse_vec(vec!("--files".to_string(), "--dry".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::MissingValue);
+        assert_eq!(error.message(), "\"--files\" takes at least 1 value");
+    }
+
+    #[test]
+    fn implications() {
+        let args = vec!(
+            "-a".to_string(),
+            "--links=no".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("archive")
+                        .flag(false)
+                        .implies("recursive", "true")
+                        .implies("links", "true"),
+                    Arg::new("recursive")
+                        .flag(false)
+                        .implies("depth", "max"),
+                    Arg::new("links").flag(false),
+                    Arg::new("depth").option("1").required(true),
+                )
+            ).parse_vec(args);
+
+        assert!(parser.get_flag("recursive").unwrap());
+        assert!(!parser.get_flag("links").unwrap());
+        assert_eq!(parser.get_option("depth").unwrap(), "max");
+    }
+
+    #[test]
+    fn groups() {
+        let args = vec!(
+            "--file".to_string(), "in.txt".to_string(),
+            "--verbose".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("file").option(""),
+                    Arg::new("stdin").flag(false).conflicts("input"),
+                    Arg::new("url").option(""),
+                    Arg::new("verbose").flag(false).requires("input"),
+                )
+            )
+            .group(ArgGroup::new("input").args(["file", "stdin", "url"]).required(true).multiple(false))
+            .parse_vec(args);
+
+        assert_eq!(parser.get_option("file").unwrap(), "in.txt");
+        assert!(parser.get_flag("verbose").unwrap());
+    }
+
+    #[test]
+    fn settings() {
+        let args = vec!(
+            "--verb".to_string(),
+            "--off".to_string(), "-5".to_string(),
+            "-n".to_string(), "-1".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings {
+                abbreviations: true,
+                allow_hyphen_values: true,
+                strict: true,
+                ..ParserSettings::default()
+            })
+            .args(
+                vec!(
+                    Arg::new("verbose").flag(false),
+                    Arg::new("offset").option("0").value_type(ValueType::Integer),
+                    Arg::new("number").option("0").value_type(ValueType::Integer),
+                )
+            ).parse_vec(args);
+
+        assert!(parser.get_flag("verbose").unwrap());
+        assert_eq!(parser.get_int("offset").unwrap(), -5);
+        assert_eq!(parser.get_int("number").unwrap(), -1);
+    }
+
+    #[test]
+    fn subcommands() {
+        let args = vec!(
+            "--verbose".to_string(),
+            "build".to_string(),
+            "--rel".to_string(),
+            "extra".to_string(),
+            "-q".to_string(),
+        );
+
+        let mut build = ArgParser::new("build");
+        build.args(
+            vec!(
+                Arg::new("release").flag(false),
+            )
+        );
+
+        let mut own = ArgParser::new("own");
+        own.settings(ParserSettings::default());
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings {
+                abbreviations: true,
+                ..ParserSettings::default()
+            })
+            .args(
+                vec!(
+                    Arg::new("verbose").flag(false).global(true),
+                    Arg::new("quiet").flag(false).global(true),
+                )
+            )
+            .subcommand(build)
+            .subcommand(own)
+            .parse_vec(args);
+
+        assert_eq!(parser.get_subcommand_name().unwrap(), "build");
+        let build = parser.get_subcommand().unwrap();
+        assert!(build.get_flag("release").unwrap());
+        assert!(build.get_flag("verbose").unwrap());
+        assert_eq!(build.extra, vec!("extra"));
+        assert!(parser.get_flag("quiet").unwrap());
+        assert!(parser.extra.is_empty());
+    }
+
+    #[test]
+    fn sort_help() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("zebra").flag(false),
+                    Arg::new("apple").flag(false),
+                )
+            );
+
+        let names = |parser: &ArgParser| parser.sorted_args().iter().map(|arg| arg.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&parser), vec!("apple", "help", "version", "zebra"));
+        parser.sort_help(SortOrder::Declaration);
+        assert_eq!(names(&parser), vec!("help", "version", "zebra", "apple"));
+
+        // Builders are kept by ArgParser::settings in either order.
+        parser.settings(ParserSettings { strict: true, ..ParserSettings::default() });
+        assert_eq!(names(&parser), vec!("help", "version", "zebra", "apple"));
+        let mut after = ArgParser::new("program_lol");
+        after.settings(ParserSettings { strict: true, ..ParserSettings::default() }).require_args(true);
+        let mut before = ArgParser::new("program_lol");
+        before.require_args(true).settings(ParserSettings { strict: true, ..ParserSettings::default() });
+        for parser in [after, before] {
+            assert_eq!(parse_ok!(parser, [] as [&str; 0]).outcome(), ParseOutcome::HelpPrinted);
+            parse_err!(parser, ["--bogus"], ErrorKind::UnknownArgument);
+        }
+    }
+
+    #[test]
+    fn inherited_sort_help() {
+        let mut sub = ArgParser::new("sub");
+        sub.sort_help(SortOrder::Declaration).args(vec!(Arg::new("zebra").flag(false), Arg::new("apple").flag(false)));
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { abbreviations: true, ..ParserSettings::default() })
+            .subcommand(sub);
+        parser.try_parse_vec(vec!("sub".to_string(), "--zeb".to_string())).unwrap();
+
+        let sub = parser.get_subcommand().unwrap();
+        assert!(sub.get_flag("zebra").unwrap());
+        let names: Vec<_> = sub.sorted_args().iter().map(|arg| arg.name.clone()).collect();
+        assert_eq!(names, vec!("help", "version", "zebra", "apple"));
+    }
+
+    #[test]
+    fn on_match() {
+        use std::sync::{Arc, Mutex};
+
+        let args = vec!(
+            "-D".to_string(), "a=1".to_string(),
+            "--verbose".to_string(),
+            "--define=b=2".to_string(),
+        );
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let defines = Arc::clone(&seen);
+        let flags = Arc::clone(&seen);
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("define")
+                        .short('D')
+                        .option("")
+                        .on_match(move |val| defines.lock().unwrap().push(format!("define {}", val))),
+                    Arg::new("verbose")
+                        .flag(false)
+                        .on_match(move |val| flags.lock().unwrap().push(format!("verbose {}", val))),
+                )
+            ).parse_vec(args);
+
+        assert_eq!(*seen.lock().unwrap(), vec!("define a=1", "verbose true", "define b=2"));
+
+        let tags = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&tags);
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("tag")
+                        .repeatable(true)
+                        .option("")
+                        .on_match(move |val| seen.lock().unwrap().push(String::from(val))),
+                )
+            ).parse_vec(vec!("--tag".to_string(), "a".to_string(), "--tag".to_string(), "b".to_string(),
+                "--tag".to_string(), "c".to_string()));
+
+        assert_eq!(*tags.lock().unwrap(), vec!("a", "b", "c"));
+        assert_eq!(parser.get_values("tag").unwrap(), vec!("a", "b", "c"));
+    }
+
+    #[test]
+    fn parse_hooks() {
+        let args = vec!(
+            "-q".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("quiet").flag(false),
+                    Arg::new("level").option("1"),
+                )
+            )
+            .before_parse(|args| {
+                // Alias expansion
+                if let Some(idx) = args.iter().position(|arg| arg == "-q") {
+                    args.splice(idx..=idx, vec!("--quiet".to_string(), "--level".to_string(), "0".to_string()));
+                }
+                Ok(())
+            })
+            .after_parse(|parser| {
+                if parser.get_flag("quiet").unwrap() && parser.get_option("level").unwrap() != "0" {
+                    return Err(String::from("--quiet needs --level 0"));
+                }
+                Ok(())
+            })
+            .parse_vec(args);
+
+        assert!(parser.get_flag("quiet").unwrap());
+        assert_eq!(parser.get_option("level").unwrap(), "0");
+    }
+
+    #[test]
+    fn errors() {
+        let parser = || {
+            let mut parser = ArgParser::new("program_lol");
+            parser.args(
+                    vec!(
+                        Arg::new("count").option("0").value_type(ValueType::Integer),
+                        Arg::new("size").option("").num_values(2),
+                        Arg::new("word").word(WordType::string("")),
+                        Arg::new("input").option("").required(true),
+                    )
+                );
+            parser
+        };
+        let kind = |args: &[&str]| parser()
+            .try_parse_vec(args.iter().map(|arg| arg.to_string()).collect())
+            .err()
+            .map(|e| e.kind());
+
+        assert_eq!(kind(&["--input", "x"]), None);
+        assert_eq!(kind(&["--input", "x", "--bogus"]), Some(ErrorKind::UnknownArgument));
+        assert_eq!(kind(&["--input", "x", "--word"]), Some(ErrorKind::UnknownArgument));
+        assert_eq!(kind(&["--input", "x", "--count=ten"]), Some(ErrorKind::InvalidValue));
+        assert_eq!(kind(&["--input", "x", "--size", "1"]), Some(ErrorKind::MissingValue));
+        assert_eq!(kind(&["--count", "1"]), Some(ErrorKind::MissingRequired));
+
+        let mut unknown = ArgParser::new("program_lol");
+        unknown.args(vec!(&mut Arg::new("unknown")));
+        assert_eq!(unknown.try_parse_vec(Vec::new()).err().unwrap().kind(), ErrorKind::Definition);
+    }
+
+    #[test]
+    fn invalid_names() {
+        let invalid = |arg: &mut Arg| {
+            let mut parser = ArgParser::new("program_lol");
+            parser.args(vec!(arg));
+            parser.try_parse_vec(Vec::new()).err().map(|e| e.kind()) == Some(ErrorKind::Definition)
+        };
+
+        assert!(invalid(Arg::new("").flag(false)));
+        assert!(invalid(Arg::new("two words").flag(false)));
+        assert!(invalid(Arg::new("--dashed").option("")));
+        assert!(invalid(Arg::new("a=b").option("")));
+        assert!(invalid(Arg::new("dash").short('-').flag(false)));
+        assert!(invalid(Arg::new("space").short(' ').flag(false)));
+        assert!(!invalid(Arg::new("key=value").word(WordType::boolean(false))));
+        assert!(!invalid(Arg::new("dry-run").flag(false)));
+    }
+
+    #[test]
+    fn unicode_shorts() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("umlaut").short('ä').flag(false),
+                    Arg::new("sigma").short('σ').option(""),
+                    Arg::new("plain").short('a').flag(false),
+                )
+            ).parse_vec(vec!("-äσ".to_string(), "value".to_string()));
+
+        assert!(parser.get_flag("umlaut").unwrap());
+        assert_eq!(parser.get_option("sigma").unwrap(), "value");
+
+        // 'a' followed by a combining diaeresis isn't -a
+        let error = parser.try_parse_vec(vec!("-a\u{308}".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::UnknownArgument);
+        assert!(!parser.get_flag("plain").unwrap());
+        assert_eq!(parser.try_parse_vec(vec!("-x".to_string())).err().unwrap().kind(), ErrorKind::UnknownArgument);
+
+        let mut combining = ArgParser::new("program_lol");
+        combining.args(vec!(Arg::new("mark").short('\u{308}').flag(false)));
+        assert_eq!(combining.try_parse_vec(Vec::new()).err().unwrap().kind(), ErrorKind::Definition);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn os_values_after_hooks() {
+        use std::ffi::{OsStr, OsString};
+        use std::os::unix::ffi::OsStrExt;
+
+        let raw = OsStr::from_bytes(b"caf\xe9").to_os_string();
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("name").option("")))
+            .before_parse(|args| {
+                args.insert(0, String::from("first"));
+                args.reverse();
+                Ok(())
+            })
+            .parse_os_vec(vec!(OsString::from("--name"), raw.clone(), OsString::from("a")));
+
+        assert_eq!(parser.get_option("name").unwrap(), "first");
+        assert_eq!(parser.extra, vec!("a", "caf\u{fffd}"));
+        assert_eq!(parser.extra_os, vec!(OsString::from("a"), raw));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn os_values() {
+        use std::ffi::{OsStr, OsString};
+        use std::os::unix::ffi::OsStrExt;
+
+        let raw = OsStr::from_bytes(b"caf\xe9").to_os_string();
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("name").option(""),
+                    Arg::new("file").value_type(ValueType::Path).option(""),
+                    Arg::new("count").value_type(ValueType::Integer).option("0"),
+                )
+            ).parse_os_vec(vec!(
+                OsString::from("--name"), raw.clone(),
+                OsString::from("--file=x"),
+                raw.clone(),
+            ));
+
+        assert_eq!(parser.get_os_option("name").unwrap(), raw);
+        assert_eq!(parser.get_option("name").unwrap(), "caf\u{fffd}");
+        assert_eq!(parser.get_path("file").unwrap(), std::path::PathBuf::from("x"));
+        assert_eq!(parser.extra_os, vec!(raw.clone()));
+
+        let mut equals = OsString::from("--file=");
+        equals.push(&raw);
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("file").value_type(ValueType::Path).option(""))).parse_os_vec(vec!(equals));
+        assert_eq!(parser.get_path("file").unwrap().as_os_str(), raw);
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("count").value_type(ValueType::Integer).option("0")));
+        let error = parser.try_parse_os_vec(vec!(OsString::from("--count"), raw)).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidUtf8);
+    }
+
+    #[test]
+    fn argv0_name() {
+        let mut parser = ArgParser::from_argv0();
+        parser.apply_argv0(std::ffi::OsStr::new("/usr/local/bin/renamed.exe"));
+        assert_eq!(parser.get_name(), "renamed");
+
+        let mut parser = ArgParser::from_argv0();
+        parser.name("program_lol").apply_argv0(std::ffi::OsStr::new("renamed"));
+        assert_eq!(parser.get_name(), "program_lol");
+        assert_eq!(parser.get_help().usage, "program_lol [flags] [options]");
+
+        let mut parser = ArgParser::from_argv0();
+        parser.apply_argv0(std::ffi::OsStr::new("renamed"));
+        assert_eq!(parser.get_help().usage, "renamed [flags] [options]");
+
+        let mut parser = ArgParser::new("old");
+        parser.name("new");
+        assert_eq!(parser.get_help().usage, "new [flags] [options]");
+        parser.usage("{name} FILE").name("newer");
+        assert_eq!(parser.get_help().usage, "newer FILE");
+    }
+
+    #[test]
+    fn multicall() {
+        use std::ffi::OsString;
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { multicall: true, ..ParserSettings::default() });
+        parser.subcommand({
+            let mut compress = ArgParser::new("compress");
+            compress.args(vec!(Arg::new("level").short('l').option("6")));
+            compress
+        }).subcommand(ArgParser::new("decompress"));
+
+        parser.try_parse_argv(vec!(OsString::from("/bin/compress"), OsString::from("-l"), OsString::from("9"))).unwrap();
+        assert_eq!(parser.get_subcommand_name().unwrap(), "compress");
+        assert_eq!(parser.get_subcommand().unwrap().get_option("level").unwrap(), "9");
+
+        // Invoked as the binary itself, the subcommand is the first argument as usual.
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { multicall: true, ..ParserSettings::default() });
+        parser.subcommand(ArgParser::new("decompress"));
+        parser.try_parse_argv(vec!(OsString::from("program_lol"), OsString::from("decompress"))).unwrap();
+        assert_eq!(parser.get_subcommand_name().unwrap(), "decompress");
+    }
+
+    #[test]
+    fn cargo_macros() {
+        let mut parser = ArgParser::new(crate_name!());
+        parser.version(crate_version!())
+            .author(crate_authors!())
+            .info(crate_description!());
+
+        assert_eq!(parser.get_name(), "rargsxd");
+        assert_eq!(crate_version!(), env!("CARGO_PKG_VERSION"));
+        assert_eq!(crate_authors!("\n"), "BubbyRoosh");
+        assert_eq!(crate_parser!().get_name(), "rargsxd");
+    }
+
+    #[test]
+    fn help_model() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.version("0.1.0")
+            .args(
+                vec!(
+                    Arg::new("output").help("Where to write").option("out.txt"),
+                    Arg::new("mode").word(WordType::string("")),
+                )
+            ).parse_vec(vec!("--output".to_string(), "other.txt".to_string()));
+
+        let help = parser.get_help();
+        let titles: Vec<&str> = help.sections.iter().map(|section| section.title.as_str()).collect();
+        assert_eq!(titles, vec!("Flags", "Options", "Words"));
+
+        let output = &help.sections[1].entries[0];
+        assert_eq!(output.short, Some('o'));
+        assert_eq!(output.long.as_deref(), Some("output"));
+        assert_eq!(output.metavar.as_deref(), Some("OUTPUT"));
+        assert_eq!(output.default.as_deref(), Some("out.txt"));
+
+        let mode = &help.sections[2].entries[0];
+        assert_eq!((mode.short, mode.long.as_deref(), mode.default.as_deref()), (None, None, None));
+        assert!(help.to_string().contains("\t-o, --output <OUTPUT>  Where to write [default: out.txt]\n"));
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("token").hide_default(true).option("secret")));
+        assert_eq!(parser.get_help().sections[1].entries[0].default, None);
+        assert!(!parser.get_help().to_string().contains("secret"));
+    }
+
+    #[test]
+    fn single_dash_long() {
+        let args = vec!(
+            "-name".to_string(), "*.rs".to_string(),
+            "-Xmx2g".to_string(),
+            "-type=f".to_string(),
+            "-d".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { single_dash_long: true, ..ParserSettings::default() })
+            .args(
+                vec!(
+                    Arg::new("name").option(""),
+                    Arg::new("type").option(""),
+                    Arg::new("X").option(""),
+                    Arg::new("Xmx").option(""),
+                    Arg::new("depth").flag(false),
+                    Arg::new("all").flag(false),
+                )
+            ).parse_vec(args);
+
+        assert_eq!(parser.get_option("name").unwrap(), "*.rs");
+        assert_eq!(parser.get_option("Xmx").unwrap(), "2g");
+        assert_eq!(parser.get_option("type").unwrap(), "f");
+        assert!(parser.get_flag("depth").unwrap());
+
+        // Shorts can't be combined.
+        let error = parser.try_parse_vec(vec!("-da".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::UnknownArgument);
+    }
+
+    #[test]
+    fn posix() {
+        let args = vec!(
+            "-a".to_string(),
+            "file".to_string(),
+            "-b".to_string(),
+            "--".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { posix: true, ..ParserSettings::default() })
+            .args(
+                vec!(
+                    Arg::new("a").flag(false),
+                    Arg::new("b").flag(false),
+                    Arg::new("W").flag(false),
+                )
+            ).parse_vec(args);
+
+        assert!(parser.get_flag("a").unwrap());
+        assert!(!parser.get_flag("b").unwrap());
+        assert_eq!(parser.extra, vec!("file", "-b", "--"));
+
+        assert_eq!(parser.try_parse_vec(vec!("--a".to_string())).err().unwrap().kind(), ErrorKind::UnknownArgument);
+        assert_eq!(parser.try_parse_vec(vec!("-W".to_string())).err().unwrap().kind(), ErrorKind::UnknownArgument);
+
+        // -- ends options without the setting too.
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("a").flag(false)))
+            .parse_vec(vec!("--".to_string(), "-a".to_string()));
+        assert!(!parser.get_flag("a").unwrap());
+        assert_eq!(parser.extra, vec!("-a"));
+    }
+
+    #[test]
+    fn possible_values() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("format").help("Output format").possible_values(vec!("json", "yaml", "text")).option("text"),
+                    Arg::new("quiet").flag(false),
+                )
+            ).parse_vec(vec!("--format".to_string(), "yaml".to_string()));
+
+        assert_eq!(parser.get_option("format").unwrap(), "yaml");
+        let error = parser.try_parse_vec(vec!("-f".to_string(), "xml".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidValue);
+
+        let bash = parser.completions(Shell::Bash);
+        assert!(bash.contains("--format|-f) COMPREPLY=($(compgen -W 'json yaml text' -- \"$cur\"))"));
+        assert!(bash.contains("complete -F _program_lol program_lol"));
+        assert!(parser.completions(Shell::Zsh).contains("'--format[Output format]:format:(json yaml text)'"));
+        assert!(parser.completions(Shell::Fish).contains("complete -c program_lol -l format -s f -d 'Output format' -r -f -a 'json yaml text'"));
+
+        // Wrapped at 80 columns however wide $COLUMNS is.
+        let help = parser.max_term_width(80).get_help().to_string();
+        assert!(help.contains(&format!("\t-f, --format <FORMAT>  Output format [default: text]\n\t{}[possible values: json, yaml, text]\n", " ".repeat(23))));
+    }
+
+    #[test]
+    fn is_present() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("given").option("default"),
+                    Arg::new("defaulted").option("default"),
+                )
+            ).parse_vec(vec!("--given=default".to_string()));
+
+        assert!(parser.is_present("given"));
+        assert!(!parser.is_present("defaulted"));
+        assert!(!parser.is_present("nonexistent"));
+        assert_eq!(parser.get_option_if_set("given").unwrap(), "default");
+        assert_eq!(parser.get_option_if_set("defaulted"), None);
+    }
+
+    #[test]
+    fn clusters() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("zip").flag(false),
+                    Arg::new("xtract").flag(false),
+                    Arg::new("file").option(""),
+                )
+            ).parse_vec(vec!("-zxf".to_string(), "archive.tar".to_string()));
+
+        assert!(parser.get_flag("zip").unwrap());
+        assert!(parser.get_flag("xtract").unwrap());
+        assert_eq!(parser.get_option("file").unwrap(), "archive.tar");
+
+        // Each option in a cluster takes its own value, in order.
+        parser.parse_vec(vec!("-fz".to_string(), "other.tar".to_string()));
+        assert_eq!(parser.get_option("file").unwrap(), "other.tar");
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("file").option(""),
+                    Arg::new("output").option(""),
+                )
+            ).parse_vec(vec!("-fo".to_string(), "archive.tar".to_string(), "out".to_string()));
+        assert_eq!(parser.get_option("file").unwrap(), "archive.tar");
+        assert_eq!(parser.get_option("output").unwrap(), "out");
+        assert_eq!(parser.get_occurrences("output").unwrap()[0].tokens, vec!("-fo", "out"));
+
+        // -f (--file and --format) would've given both "archive.tar".
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("file").option(""),
+                    Arg::new("format").option(""),
+                )
+            );
+        let error = parser.try_parse_vec(vec!("-f".to_string(), "archive.tar".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::Ambiguous);
+        assert_eq!(parser.get_option("file").unwrap(), "");
+    }
+
+    #[test]
+    fn short_equals() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("output").option("default"),
+                    Arg::new("name").option("default"),
+                    Arg::new("all").flag(false),
+                    Arg::new("quiet").flag(true),
+                )
+            ).parse_vec(vec!("-ao=file=1".to_string(), "-n=".to_string(), "-q=no".to_string()));
+
+        assert!(parser.get_flag("all").unwrap());
+        assert_eq!(parser.get_option("output").unwrap(), "file=1");
+        assert_eq!(parser.get_option("name").unwrap(), "");
+        assert!(!parser.get_flag("quiet").unwrap());
+    }
+
+    #[test]
+    fn repeatable() {
+        let args = vec!(
+            "lint".to_string(), "a.rs".to_string(), "b.rs".to_string(),
+            "-I".to_string(), "src".to_string(),
+            "-I".to_string(), "tests".to_string(),
+            "lint".to_string(), "c.rs".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("lint").repeatable(true).min_values(1).word(WordType::string("")),
+                    Arg::new("include").short('I').repeatable(true).option(""),
+                )
+            ).parse_vec(args);
+
+        assert_eq!(parser.get_values("lint").unwrap(), vec!("a.rs", "b.rs", "c.rs"));
+        assert_eq!(parser.get_values("include").unwrap(), vec!("src", "tests"));
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("lint").repeatable(true).min_values(1).word(WordType::string(""))));
+        let error = parser.try_parse_vec(vec!("lint".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::MissingValue);
+    }
+
+    #[test]
+    fn env_fallback() {
+        std::env::set_var("RARGSXD_TEST_TOKEN", "from env");
+        std::env::set_var("RARGSXD_TEST_VERBOSE", "yes");
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("token").help("API token").env("RARGSXD_TEST_TOKEN").option(""),
+                    Arg::new("verbose").env("RARGSXD_TEST_VERBOSE").flag(false),
+                    Arg::new("passed").env("RARGSXD_TEST_TOKEN").option(""),
+                    Arg::new("unset").env("RARGSXD_TEST_UNSET").option("default"),
+                )
+            ).parse_vec(vec!("--passed".to_string(), "from args".to_string()));
+
+        assert_eq!(parser.get_option("token").unwrap(), "from env");
+        assert!(parser.get_flag("verbose").unwrap());
+        assert_eq!(parser.get_option("passed").unwrap(), "from args");
+        assert_eq!(parser.get_option("unset").unwrap(), "default");
+        assert!(parser.get_help().to_string().contains("\t-t, --token <TOKEN>    API token [env: RARGSXD_TEST_TOKEN]\n"));
+    }
+
+    #[test]
+    fn only_on() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("here").only_on(vec!(std::env::consts::OS, "plan9")).flag(false),
+                    Arg::new("family").only_on(vec!(std::env::consts::FAMILY)).flag(false),
+                    Arg::new("elsewhere").only_on(vec!("plan9")).flag(false),
+                )
+            );
+
+        assert!(parser.get_flag("here").is_some());
+        assert!(parser.get_flag("family").is_some());
+        assert!(parser.get_flag("elsewhere").is_none());
+        assert!(!parser.completions(Shell::Bash).contains("elsewhere"));
+    }
+
+    #[test]
+    fn replaced_by() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("output").option(""),
+                    Arg::new("outfile").replaced_by("output"),
+                )
+            );
+
+        let error = parser.try_parse_vec(vec!("--outfile=x".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::UnknownArgument);
+        assert_eq!(error.message(), "'--outfile' was renamed to '--output'");
+        assert!(parser.get_option("outfile").is_none());
+    }
+
+    #[test]
+    fn topics() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.topic("patterns", "Patterns are globs: * matches anything.");
+
+        assert_eq!(parser.get_topic("patterns").unwrap(), "Patterns are globs: * matches anything.");
+        assert!(parser.get_help().to_string().contains("\nTopics (help <topic>):\n\tpatterns\n"));
+
+        let error = parser.try_parse_vec(vec!("help".to_string(), "config".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::UnknownArgument);
+        assert_eq!(error.message(), "No help topic \"config\" (topics: patterns)");
+        assert!(parser.try_parse_vec(vec!("--help=config".to_string())).is_err());
+    }
+
+    #[test]
+    fn occurrences() {
+        let args = vec!(
+            "--color=auto".to_string(),
+            "-Vc".to_string(), "never".to_string(),
+            "mode".to_string(), "fast".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("color").option(""),
+                    Arg::new("verbose").short('V').flag(false),
+                    Arg::new("mode").word(WordType::string("")),
+                )
+            ).parse_vec(args);
+
+        let occurrence = |index: usize, tokens: &[&str]| Occurrence { index, tokens: tokens.iter().map(|t| t.to_string()).collect() };
+        assert_eq!(parser.get_occurrences("color").unwrap(), vec!(occurrence(0, &["--color=auto"]), occurrence(1, &["-Vc", "never"])));
+        assert_eq!(parser.get_occurrences("verbose").unwrap(), vec!(occurrence(1, &["-Vc"])));
+        assert_eq!(parser.get_occurrences("mode").unwrap(), vec!(occurrence(3, &["mode", "fast"])));
+    }
+
+    #[test]
+    fn collect_unknown() {
+        let args = vec!(
+            "--backend-opt=1".to_string(),
+            "-q".to_string(),
+            "-Z".to_string(),
+            "file".to_string(),
+            "--own".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { collect_unknown: true, ..ParserSettings::default() })
+            .args(
+                vec!(
+                    Arg::new("quiet").flag(false),
+                    Arg::new("own").flag(false),
+                )
+            ).parse_vec(args);
+
+        assert_eq!(parser.unknown_flags, vec!("--backend-opt=1", "-Z"));
+        assert_eq!(parser.extra, vec!("file"));
+        assert!(parser.get_flag("quiet").unwrap());
+        assert!(parser.get_flag("own").unwrap());
+    }
+
+    #[test]
+    fn forward_unknown() {
+        let args = vec!(
+            "--new-mode".to_string(),
+            "fast".to_string(),
+            "--colour=always".to_string(),
+            "file".to_string(),
+            "--dry-run".to_string(),
+            "-q".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { forward_unknown: true, ..ParserSettings::default() })
+            .args(
+                vec!(
+                    Arg::new("quiet").flag(false),
+                )
+            ).parse_vec(args);
+
+        assert_eq!(parser.forwarded, vec!("--new-mode", "fast", "--colour=always", "--dry-run"));
+        assert_eq!(parser.extra, vec!("file"));
+        assert!(parser.get_flag("quiet").unwrap());
+
+        assert_output!(parser, ["--new-mode", "fast", "-q"], "warning: ignoring unknown argument \"--new-mode\"\n");
+        parser.settings(ParserSettings { forward_unknown: true, warn_forwarded: false, ..ParserSettings::default() });
+        assert_output!(parser, ["--new-mode", "fast", "-q"], "");
+    }
+
+    #[test]
+    fn underscores_as_hyphens() {
+        let args = vec!(
+            "--dry_run".to_string(),
+            "--max-depth=3".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { underscores_as_hyphens: true, ..ParserSettings::default() })
+            .args(
+                vec!(
+                    Arg::new("dry-run").flag(false),
+                    Arg::new("max_depth").option(""),
+                )
+            ).parse_vec(args);
+
+        assert!(parser.get_flag("dry-run").unwrap());
+        assert_eq!(parser.get_option("max_depth").unwrap(), "3");
+        let help = parser.get_help().to_string();
+        assert!(help.contains("--dry-run") && help.contains("--max_depth"));
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("dry-run").flag(false)));
+        let error = parser.try_parse_vec(vec!("--dry_run".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::UnknownArgument);
+    }
+
+    #[test]
+    fn flag_tristate() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("color").flag(true),
+                    Arg::new("verbose").flag(false),
+                    Arg::new("quiet").flag(false),
+                    Arg::new("level").option("3"),
+                )
+            ).parse_vec(vec!("--color=false".to_string(), "--verbose".to_string()));
+
+        assert_eq!(parser.get_flag_tristate("color"), Some(Some(false)));
+        assert_eq!(parser.get_flag_tristate("verbose"), Some(Some(true)));
+        assert_eq!(parser.get_flag_tristate("quiet"), Some(None));
+        assert_eq!(parser.get_flag_tristate("level"), None);
+        assert_eq!(parser.get_flag_tristate("missing"), None);
+    }
+
+    #[test]
+    fn testing_helpers() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.version("0.1.0")
+            .args(
+                vec!(
+                    Arg::new("verbose").flag(false),
+                    Arg::new("output").option(""),
+                )
+            );
+
+        let parsed = parse_ok!(parser, ["--verbose", "-o", "x"]);
+        assert!(parsed.get_flag("verbose").unwrap());
+        assert_eq!(parsed.get_option("output").unwrap(), "x");
+        assert!(!parser.get_flag("verbose").unwrap());
+
+        let error = parse_err!(parser, ["--bogus"], ErrorKind::UnknownArgument);
+        assert_eq!(error.message(), "Unexpected argument: \"--bogus\"");
+
+        assert_output!(parser, ["--version"], "program_lol 0.1.0\n");
+        assert_output!(parser, ["-h"], parser.get_help().to_string());
+        assert_output!(parser, ["-o", "x"], "");
+        assert_eq!(parse_ok!(parser, ["--help"]).outcome(), ParseOutcome::HelpPrinted);
+    }
+
+    #[test]
+    fn short_circuit_returns() {
+        use std::sync::{Arc, Mutex};
+
+        let ran = Arc::new(Mutex::new(false));
+        let callback = Arc::clone(&ran);
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("list-formats").flag(false).short_circuit(move |_| *callback.lock().unwrap() = true),
+                    Arg::new("input").required(true).option(""),
+                )
+            );
+
+        assert_eq!(parse_ok!(parser, ["--list-formats"]).outcome(), ParseOutcome::ShortCircuited);
+        assert!(*ran.lock().unwrap());
+
+        parser.settings(ParserSettings { return_on_info: true, ..ParserSettings::default() });
+        assert_eq!(parse(&parser, &["--list-formats"]).unwrap().outcome(), ParseOutcome::ShortCircuited);
+    }
+
+    #[test]
+    #[should_panic(expected = "parsing [\"--bogus\"] failed")]
+    fn testing_helpers_panic() {
+        let parser = ArgParser::new("program_lol");
+        parse_ok!(parser, ["--bogus"]);
+    }
+
+    #[test]
+    fn forward() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("verbose").flag(false),
+                    Arg::new("exec").repeatable(true).word(WordType::string("")),
+                )
+            ).parse_vec(vec!(
+                "extra".to_string(),
+                "exec".to_string(), "ls".to_string(), "my dir".to_string(),
+                "--verbose".to_string(),
+                "--".to_string(), "grep".to_string(), "-v".to_string(), "a b".to_string(),
+            ));
+
+        assert!(parser.get_flag("verbose").unwrap());
+        assert_eq!(parser.get_trailing(), ["grep", "-v", "a b"]);
+        assert_eq!(parser.extra, vec!("extra", "grep", "-v", "a b"));
+
+        let mut command = std::process::Command::new("env");
+        parser.forward(&mut command);
+        assert_eq!(command.get_args().collect::<Vec<_>>(), vec!("grep", "-v", "a b"));
+
+        let mut command = std::process::Command::new("env");
+        parser.forward_values("exec", &mut command);
+        assert_eq!(command.get_args().collect::<Vec<_>>(), vec!("ls", "my dir"));
+    }
+
+    #[test]
+    fn digit_shorts() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { allow_hyphen_values: true, ..ParserSettings::default() })
+            .args(
+                vec!(
+                    Arg::new("level").value_type(ValueType::Unsigned).digit_shorts(true).option("6"),
+                    Arg::new("offset").value_type(ValueType::Integer).option("0"),
+                )
+            ).parse_vec(vec!("-9".to_string(), "--offset".to_string(), "-5".to_string()));
+
+        assert_eq!(parser.get_uint("level").unwrap(), 9);
+        assert_eq!(parser.get_int("offset").unwrap(), -5);
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("fast").digit_shorts(true).flag(false)));
+        assert_eq!(parser.try_parse_vec(Vec::new()).err().unwrap().kind(), ErrorKind::Definition);
+    }
+
+    #[test]
+    fn long_help() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("color").help("When to color").long_help("When to color output.\nauto colors terminals.").flag(false),
+                    Arg::new("quiet").help("Prints less").flag(false),
+                )
+            );
+
+        let help = parser.get_help().to_string();
+        assert!(help.contains("\t-c, --color    When to color\n"));
+        let long_help = parser.get_long_help().to_string();
+        assert!(long_help.contains(&format!("\t-c, --color    When to color output.\n\t{}auto colors terminals.\n", " ".repeat(15))));
+        assert!(long_help.contains("\t-q, --quiet    Prints less\n"));
+    }
+
+    #[test]
+    fn hanging_indent() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("configuration").help("Where to look.\nDefaults to here.").option(""),
+                    Arg::new("quiet").help("Prints less.\nPrints errors still.").flag(false),
+                )
+            );
+
+        let help = parser.get_help().to_string();
+        assert!(help.contains(&format!("\t-c, --configuration <CONFIGURATION>  Where to look.\n\t{}Defaults to here.\n", " ".repeat(37))));
+        assert!(help.contains(&format!("\t-q, --quiet{}Prints less.\n\t{}Prints errors still.\n", " ".repeat(26), " ".repeat(37))));
+    }
+
+    #[test]
+    fn unicode_width() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("名前").short('n').help("名前を設定する").option(""),
+                    Arg::new("quiet").help("Prints less").flag(false),
+                )
+            );
+
+        // "-n, --名前 <名前>" is 17 columns wide.
+        let help = parser.get_help().to_string();
+        assert!(help.contains("\t-n, --名前 <名前>  名前を設定する\n"));
+        assert!(help.contains("\t-q, --quiet        Prints less\n"));
+    }
+
+    #[test]
+    fn usage_groups() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("json").flag(false),
+                    Arg::new("yaml").flag(false),
+                    Arg::new("file").option(""),
+                    Arg::new("stdin").flag(false),
+                )
+            )
+            .group(ArgGroup::new("format").args(["json", "yaml"]).multiple(false))
+            .group(ArgGroup::new("input").args(["file", "stdin"]).required(true));
+
+        assert_eq!(parser.get_help().usage, "program_lol [flags] [options] [--json | --yaml] (--file <FILE> | --stdin)");
+        parser.usage("program_lol [--json] <file>");
+        assert_eq!(parser.get_help().usage, "program_lol [--json] <file>");
+    }
+
+    #[test]
+    fn positionals() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("input").positional(true).required(true).option(""),
+                    Arg::new("verbose").flag(false),
+                    Arg::new("rest").positional(true).repeatable(true).option(""),
+                )
+            ).parse_vec(vec!("in.txt".to_string(), "--verbose".to_string(), "a".to_string(), "b".to_string()));
+
+        assert_eq!(parser.get_option("input").unwrap(), "in.txt");
+        assert_eq!(parser.get_values("rest").unwrap(), vec!("a", "b"));
+        assert!(parser.extra.is_empty());
+        assert_eq!(parser.get_help().sections[0].title, "Arguments");
+
+        let error = parser.try_parse_vec(vec!("--input".to_string(), "x".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::UnknownArgument);
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("output").positional(true).option(""),
+                    Arg::new("input").positional(true).required(true).option(""),
+                )
+            );
+        let error = parser.try_parse_vec(vec!()).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::Definition);
+        assert_eq!(error.message(), "Required positional \"input\" comes after optional positional \"output\"");
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("files").positional(true).repeatable(true).option(""),
+                    Arg::new("output").positional(true).option(""),
+                )
+            );
+        let error = parser.try_parse_vec(vec!()).err().unwrap();
+        assert_eq!(error.message(), "Positional \"files\" is repeatable but isn't the last positional");
+    }
+
+    #[test]
+    fn positional_usage() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("input").positional(true).required(true).option(""),
+                    Arg::new("output").positional(true).option(""),
+                    Arg::new("rest").positional(true).repeatable(true).option(""),
+                )
+            );
+
+        assert_eq!(parser.get_help().usage, "program_lol [flags] [options] <INPUT> [OUTPUT] [REST]...");
+    }
+
+    #[test]
+    fn last() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("verbose").flag(false),
+                    Arg::new("command").positional(true).last(true).option(""),
+                )
+            ).parse_vec(vec!("--verbose".to_string(), "--".to_string(), "ls".to_string(), "-la".to_string()));
+
+        assert!(parser.get_flag("verbose").unwrap());
+        assert_eq!(parser.get_values("command").unwrap(), vec!("ls", "-la"));
+        assert_eq!(parser.get_help().usage, "program_lol [flags] [options] [--] [COMMAND]...");
+
+        parser.try_parse_vec(vec!("echo".to_string(), "--verbose".to_string())).unwrap();
+        assert_eq!(parser.get_values("command").unwrap(), vec!("echo", "--verbose"));
+        assert!(parser.extra.is_empty());
+    }
+
+    #[test]
+    fn trailing_var_arg() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { trailing_var_arg: true, ..ParserSettings::default() })
+            .args(
+                vec!(
+                    Arg::new("release").flag(false),
+                    Arg::new("bin").positional(true).option(""),
+                    Arg::new("args").positional(true).option(""),
+                )
+            ).parse_vec(vec!("--release".to_string(), "app".to_string(), "in.txt".to_string(), "--release".to_string(), "-x".to_string()));
+
+        assert!(parser.get_flag("release").unwrap());
+        assert_eq!(parser.get_option("bin").unwrap(), "app");
+        assert_eq!(parser.get_values("args").unwrap(), vec!("in.txt", "--release", "-x"));
+    }
+
+    #[test]
+    fn info_after_trailing_positional() {
+        let args = || vec!("ls".to_string(), "-h".to_string());
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { trailing_var_arg: true, return_on_info: true, ..ParserSettings::default() })
+            .args(vec!(Arg::new("cmd").positional(true).option("")))
+            .parse_vec(args());
+        assert_eq!(parser.outcome(), ParseOutcome::Parsed);
+        assert_eq!(parser.get_values("cmd").unwrap(), vec!("ls", "-h"));
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { return_on_info: true, ..ParserSettings::default() })
+            .args(vec!(Arg::new("cmd").positional(true).repeatable(true).last(true).option("")))
+            .parse_vec(args());
+        assert_eq!(parser.outcome(), ParseOutcome::Parsed);
+        assert_eq!(parser.get_values("cmd").unwrap(), vec!("ls", "-h"));
+    }
+
+    #[test]
+    fn info_as_option_value() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { allow_hyphen_values: true, return_on_info: true, ..ParserSettings::default() })
+            .args(vec!(Arg::new("grep").option(""), Arg::new("quiet").flag(false)))
+            .parse_vec(vec!("--grep".to_string(), "-v".to_string(), "-q".to_string()));
+
+        assert_eq!(parser.outcome(), ParseOutcome::Parsed);
+        assert_eq!(parser.get_option("grep").unwrap(), "-v");
+        assert!(parser.get_flag("quiet").unwrap());
+
+        parser.parse_vec(vec!("--grep".to_string(), "-v".to_string(), "-h".to_string()));
+        assert_eq!(parser.outcome(), ParseOutcome::HelpPrinted);
+    }
+
+    #[test]
+    fn allow_missing_positional() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { allow_missing_positional: true, ..ParserSettings::default() })
+            .args(
+                vec!(
+                    Arg::new("src").positional(true).required(true).option(""),
+                    Arg::new("middle").positional(true).option("none"),
+                    Arg::new("dst").positional(true).required(true).option(""),
+                )
+            ).parse_vec(vec!("a".to_string(), "b".to_string()));
+
+        assert_eq!(parser.get_option("src").unwrap(), "a");
+        assert_eq!(parser.get_option("middle").unwrap(), "none");
+        assert_eq!(parser.get_option("dst").unwrap(), "b");
+
+        parser.parse_vec(vec!("a".to_string(), "m".to_string(), "b".to_string()));
+        assert_eq!(parser.get_option("middle").unwrap(), "m");
+        assert_eq!(parser.get_option("dst").unwrap(), "b");
+    }
+
+    #[test]
+    fn options_first() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { options_first: true, ..ParserSettings::default() })
+            .args(vec!(Arg::new("force").flag(false)));
+
+        parser.try_parse_vec(vec!("--force".to_string(), "file.txt".to_string())).unwrap();
+        assert!(parser.get_flag("force").unwrap());
+        assert_eq!(parser.extra, vec!("file.txt"));
+
+        let error = parser.try_parse_vec(vec!("file.txt".to_string(), "--force".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::UnknownArgument);
+        assert_eq!(error.message(), "Options must come before file operands: --force");
+    }
+
+    #[test]
+    fn plus_toggle() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("xtrace").plus_toggle(true).flag(true),
+                    Arg::new("errexit").plus_toggle(true).flag(false),
+                )
+            ).parse_vec(vec!("+xe".to_string()));
+
+        assert!(!parser.get_flag("xtrace").unwrap());
+        assert!(!parser.get_flag("errexit").unwrap());
+
+        parser.parse_vec(vec!("+x".to_string(), "-x".to_string(), "-e".to_string(), "+5".to_string()));
+        assert!(parser.get_flag("xtrace").unwrap());
+        assert!(parser.get_flag("errexit").unwrap());
+        assert_eq!(parser.extra, vec!("+5"));
+    }
+
+    #[test]
+    fn get_paths() {
+        use std::path::PathBuf;
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("include").value_type(ValueType::Path).repeatable(true).option("src"),
+                    Arg::new("output").value_type(ValueType::Path).option("out"),
+                )
+            ).parse_vec(vec!("-i".to_string(), "a".to_string(), "--include=b/c".to_string()));
+
+        assert_eq!(parser.get_paths("include").unwrap(), vec!(PathBuf::from("a"), PathBuf::from("b/c")));
+        assert_eq!(parser.get_paths("output").unwrap(), vec!(PathBuf::from("out")));
+        assert!(parser.get_paths("missing").is_none());
+    }
+
+    #[test]
+    fn shell_quoting() {
+        let args = vec!("rm", "-rf", "my file", "it's", "");
+        assert_eq!(shell_join(&args, Quoting::Posix), "rm -rf 'my file' 'it'\\''s' ''");
+        assert_eq!(shell_join(&args, Quoting::Cmd), "rm -rf \"my file\" it's \"\"");
+        assert_eq!(shell_quote(r#"say "hi" C:\dir\"#, Quoting::Cmd), r#"^"say \^"hi\^" C:\dir\\^""#);
+        assert_eq!(shell_quote("a&b", Quoting::Cmd), "\"a&b\"");
+        assert_eq!(shell_quote(r#"a"&calc"#, Quoting::Cmd), r#"^"a\^"^&calc^""#);
+        assert_eq!(shell_quote("%PATH%!x!", Quoting::Cmd), "^\"^%PATH^%^!x^!^\"");
+
+        for arg in [r#"a"&calc"#, "%PATH%", r#"say "hi" & (go) | x > y ^ !z! C:\dir\"#, "a&b", "", "it's"] {
+            assert_eq!(shell_split(&shell_quote(arg, Quoting::Cmd), Quoting::Cmd).unwrap(), vec!(arg));
+        }
+    }
+
+    #[test]
+    fn kind_suggestions() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings { strict: true, ..ParserSettings::default() })
+            .args(
+                vec!(
+                    Arg::new("testword").word(WordType::boolean(false)),
+                    Arg::new("install").flag(false),
+                )
+            );
+
+        let error = parser.try_parse_vec(vec!("--testword".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::UnknownArgument);
+        assert_eq!(error.message(), "Unexpected argument: \"--testword\" (did you mean \"testword\"?)");
+        let error = parser.try_parse_vec(vec!("install".to_string())).err().unwrap();
+        assert_eq!(error.message(), "Unexpected argument: \"install\" (did you mean \"--install\"?)");
+        let error = parser.try_parse_vec(vec!("--bogus".to_string())).err().unwrap();
+        assert_eq!(error.message(), "Unexpected argument: \"--bogus\"");
+    }
+
+    #[test]
+    fn missing_required() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("user").required(true).option(""),
+                    Arg::new("host").required(true).option(""),
+                    Arg::new("port").option("22"),
+                )
+            );
+
+        let error = parser.try_parse_vec(vec!("--host".to_string(), "example.com".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::MissingRequired);
+        assert_eq!(error.message(), "Missing required argument: --user");
+        assert_eq!(parser.missing_required(), vec!("user"));
+        let host = parser.get_arg("host").unwrap();
+        assert_eq!(host.get_name(), "host");
+        assert!(host.is_required() && host.is_set());
+        assert!(!parser.get_arg("port").unwrap().is_required());
+    }
+
+    #[test]
+    fn missing_required_all() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("output").required(true).option(""),
+                    Arg::new("input").required(true).option(""),
+                    Arg::new("dest").required(true).positional(true).option(""),
+                    Arg::new("quiet").flag(false),
+                )
+            );
+
+        let error = parser.try_parse_vec(vec!("-q".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::MissingRequired);
+        assert_eq!(error.message(), "Missing required arguments: --output, --input, <DEST>");
+    }
+
+    #[test]
+    fn check_order() {
+        let error = |parser: &mut ArgParser, args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            parser.try_parse_vec(args).err().unwrap().message().to_string()
+        };
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("zip").requires("level").flag(false),
+                    Arg::new("archive").requires("level").flag(false),
+                    Arg::new("level").option(""),
+                )
+            );
+        assert_eq!(error(&mut parser, &["--archive", "--zip"]), "\"--zip\" requires \"--level\"");
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("zip").flag(false),
+                    Arg::new("archive").flag(false),
+                    Arg::new("tar").flag(false),
+                )
+            )
+            .group(ArgGroup::new("output").args(["zip", "tar"]).required(true))
+            .group(ArgGroup::new("format").args(["archive", "tar"]).required(true));
+        assert_eq!(error(&mut parser, &[]), "Didn't find one of \"output\" (--zip, --tar)");
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("width").validator(|_, _| Err(String::from("too wide"))).option(""),
+                    Arg::new("height").validator(|_, _| Err(String::from("too high"))).option(""),
+                )
+            );
+        assert!(error(&mut parser, &["--height", "1", "--width", "1"]).contains("too wide"));
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("zeta").required(true).option(""),
+                    Arg::new("alpha").required(true).option(""),
+                )
+            );
+        assert!(parser.try_parse_vec(Vec::new()).is_err());
+        assert_eq!(parser.missing_required(), vec!("zeta", "alpha"));
+
+        let ran = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (zeta, alpha) = (ran.clone(), ran.clone());
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("zeta").flag(false).short_circuit(move |_| zeta.lock().unwrap().push("zeta")),
+                    Arg::new("alpha").flag(false).short_circuit(move |_| alpha.lock().unwrap().push("alpha")),
+                )
+            );
+        parse_ok!(parser, ["--alpha", "--zeta"]);
+        assert_eq!(*ran.lock().unwrap(), vec!("zeta"));
+    }
+
+    #[test]
+    fn stdin_values() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("password").stdin(true).option("")));
+        // Only reads stdin for "-".
+        parser.parse_vec(vec!("--password".to_string(), "hunter2".to_string()));
+        assert_eq!(parser.get_option("password").unwrap(), "hunter2");
+
+        assert_eq!(crate::value::stdin_value(b"secret\r\n".to_vec()).0, "secret");
+        assert_eq!(crate::value::stdin_value(b"two\nlines\n\n".to_vec()).0, "two\nlines\n");
+    }
+
+    #[test]
+    fn dash_operand() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("file").positional(true).required(true).option("")))
+            .parse_vec(vec!("-".to_string()));
+        assert_eq!(parser.get_option("file").unwrap(), "-");
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.parse_vec(vec!("-".to_string()));
+        assert_eq!(parser.extra, vec!("-"));
+    }
+
+    /// Parses `-` for a positional that reads stdin, ran by positional_stdin with stdin piped.
+    #[test]
+    #[ignore = "reads stdin, ran by positional_stdin"]
+    fn positional_stdin_child() {
+        if std::env::var_os("RARGSXD_STDIN_CHILD").is_none() {
+            return;
+        }
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("msg").positional(true).required(true).stdin(true).option("")))
+            .parse_vec(vec!("-".to_string()));
+        assert_eq!(parser.get_option("msg").unwrap(), "from stdin");
+    }
+
+    #[test]
+    fn positional_stdin() {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        let mut child = Command::new(std::env::current_exe().unwrap())
+            .args(["tests::positional_stdin_child", "--exact", "--ignored"])
+            .env("RARGSXD_STDIN_CHILD", "1")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(b"from stdin\n").unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("1 passed"));
+    }
+
+    #[test]
+    fn version_json() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.version("1.2.0")
+            .author("A \"B\" C:D")
+            .build_info("commit", "abc123");
+
+        assert_eq!(parser.version_json(),
+            "{\"name\":\"program_lol\",\"version\":\"1.2.0\",\"authors\":[\"A \\\"B\\\" C\",\"D\"],\"build\":{\"commit\":\"abc123\"}}");
+        let error = parser.try_parse_vec(vec!("--version=yaml".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn typed_builder() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.typed_arg(ArgBuilder::new("verbose").flag(false))
+            .typed_arg(ArgBuilder::new("output").with(|arg| {arg.short('f');}).option("out.txt"))
+            .parse_vec(vec!("--verbose".to_string(), "-f".to_string(), "in.txt".to_string()));
+
+        assert!(parser.get_flag("verbose").unwrap());
+        assert_eq!(parser.get_option("output").unwrap(), "in.txt");
+    }
+
+    #[test]
+    fn parse_vec_with_bin() {
+        let mut parser = ArgParser::from_argv0();
+        parser.args(vec!(Arg::new("verbose").flag(false)))
+            .parse_vec_with_bin(vec!("/usr/bin/tool".to_string(), "--verbose".to_string()));
+
+        assert_eq!(parser.get_name(), "tool");
+        assert!(parser.get_flag("verbose").unwrap());
+        assert!(parser.extra.is_empty());
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.parse_vec_with_bin(vec!("tool".to_string(), "file".to_string()));
+        assert_eq!(parser.get_name(), "program_lol");
+        assert_eq!(parser.extra, vec!("file"));
+    }
+
+    #[test]
+    fn parse_str() {
+        assert_eq!(shell_split(r#"rm -rf 'my file' "it's" a\ b"#, Quoting::Posix).unwrap(), vec!("rm", "-rf", "my file", "it's", "a b"));
+        assert_eq!(shell_split(r#"copy "C:\My Files\\" "say ""hi""" a^&b \"q\""#, Quoting::Cmd).unwrap(),
+            vec!("copy", "C:\\My Files\\", "say \"hi\"", "a&b", "\"q\""));
+        assert_eq!(shell_split(r#"a "b\\" c\d"#, Quoting::Cmd).unwrap(), vec!("a", "b\\", "c\\d"));
+        assert!(shell_split("'open", Quoting::Posix).is_err());
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("message").option("")))
+            .try_parse_str(r#"--message "hello there""#, Quoting::Cmd).unwrap();
+        assert_eq!(parser.get_option("message").unwrap(), "hello there");
+        let error = parser.try_parse_str("--message 'hi", Quoting::Posix).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn reset() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(
+            Arg::new("quiet").flag(false),
+            Arg::new("output").option("out.txt"),
+            Arg::new("tag").repeatable(true).option(""),
+        ));
+
+        parser.try_parse_vec(vec!("--quiet", "--output", "a.txt", "--tag", "x", "extra").into_iter().map(String::from).collect()).unwrap();
+        assert!(parser.get_flag("quiet").unwrap());
+        // Parsed values are kept apart from the defaults, which parsing leaves alone.
+        let output = parser.get_arg("output").unwrap();
+        assert_eq!((output.get_type(), output.get_default()), (&ArgType::option("a.txt"), &ArgType::option("out.txt")));
+        parser.reset();
+        assert!(parser.get_args().iter().all(|arg| arg.parsed.is_none()));
+        assert!(!parser.get_flag("quiet").unwrap());
+        assert!(!parser.is_present("output"));
+        assert_eq!(parser.get_option("output").unwrap(), "out.txt");
+        assert!(parser.extra.is_empty());
+
+        parser.try_parse_vec(vec!("--quiet", "--tag", "y").into_iter().map(String::from).collect()).unwrap();
+        assert!(parser.get_flag("quiet").unwrap());
+        assert_eq!(parser.get_values("tag").unwrap(), vec!("y"));
+    }
+
+    #[test]
+    fn constructor_shortcuts() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(
+            &mut Arg::flag_new("force", 'f', "Overwrites files"),
+            &mut Arg::option_new("out", 'o', "FILE", "Where to write"),
+            &mut Arg::word_new("build", WordType::Boolean(false), "Builds it"),
+        ));
+
+        parser.try_parse_vec(vec!("-f", "build", "-o", "a.txt").into_iter().map(String::from).collect()).unwrap();
+        assert!(parser.get_flag("force").unwrap());
+        assert_eq!(parser.get_option("out").unwrap(), "a.txt");
+        assert!(parser.get_word("build").unwrap().as_bool().unwrap());
+        assert!(parser.get_help().to_string().contains("-o, --out <FILE>  Where to write"));
+    }
+
+    #[test]
+    fn owned_args() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(["red", "green", "blue"].iter().map(|name| Arg::flag_new(name, name.chars().last().unwrap(), "")))
+            .arg(Arg::option_new("out", 'o', "FILE", ""))
+            .arg(Arg::new("quiet").short('q').flag(false));
+
+        parser.try_parse_vec(vec!("-de", "-q", "-o", "a.txt").into_iter().map(String::from).collect()).unwrap();
+        assert!(parser.get_flag("red").unwrap());
+        assert!(!parser.get_flag("green").unwrap());
+        assert!(parser.get_flag("blue").unwrap());
+        assert!(parser.get_flag("quiet").unwrap());
+        assert_eq!(parser.get_option("out").unwrap(), "a.txt");
+    }
+
+    #[test]
+    fn validators() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(
+            Arg::new("min").value_type(ValueType::Integer).option("0"),
+            Arg::new("max").value_type(ValueType::Integer).option("10")
+                .validator(|max, parser| {
+                    let min: i64 = parser.get_typed("min").unwrap();
+                    if max.parse::<i64>().unwrap() < min {Err(format!("must be at least --min ({})", min))} else {Ok(())}
+                }),
+        ));
+
+        parser.try_parse_vec(vec!("--min", "3", "--max", "5").into_iter().map(String::from).collect()).unwrap();
+        parser.reset();
+        let error = parser.try_parse_vec(vec!("--max", "2", "--min", "3").into_iter().map(String::from).collect()).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidValue);
+        assert_eq!(error.message(), "Invalid value \"2\" for \"--max\": must be at least --min (3)");
+    }
+
+    #[test]
+    fn checks() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(
+            Arg::new("start").value_type(ValueType::Integer).option("0"),
+            Arg::new("end").value_type(ValueType::Integer).option("0"),
+            Arg::new("name").required(true).option(""),
+        )).check(|parser| {
+            if parser.get_typed::<i64>("start") > parser.get_typed::<i64>("end") {
+                return Err(String::from("--start must be before --end"));
+            }
+            Ok(())
+        });
+
+        // Ran before the missing --name is reported.
+        let error = parser.try_parse_vec(vec!("--start", "5", "--end", "2").into_iter().map(String::from).collect()).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::Custom);
+        assert_eq!(error.message(), "--start must be before --end");
+    }
+
+    #[test]
+    fn return_on_info() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings {
+            return_on_info: true,
+            ..ParserSettings::default()
+        }).args(vec!(Arg::new("name").required(true).option("")));
+
+        parser.try_parse_vec(vec!(String::from("--help"))).unwrap();
+        assert_eq!(parser.outcome(), ParseOutcome::HelpPrinted);
+        parser.try_parse_vec(vec!(String::from("--version"))).unwrap();
+        assert_eq!(parser.outcome(), ParseOutcome::VersionPrinted);
+        parser.try_parse_vec(vec!(String::from("--name"), String::from("x"))).unwrap();
+        assert_eq!(parser.outcome(), ParseOutcome::Parsed);
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings {
+            return_on_info: true,
+            ..ParserSettings::default()
+        }).subcommand(ArgParser::new("sub"));
+        parser.try_parse_vec(vec!(String::from("sub"), String::from("-h"))).unwrap();
+        assert_eq!(parser.outcome(), ParseOutcome::HelpPrinted);
+    }
+
+    #[test]
+    fn subcommand_help() {
+        let mut sub = ArgParser::new("build");
+        sub.info("Builds the project")
+            .example("prog build --release")
+            .args(vec!(Arg::new("release").flag(false)));
+        let mut parser = ArgParser::new("prog");
+        parser.settings(ParserSettings {
+            return_on_info: true,
+            ..ParserSettings::default()
+        }).subcommand(sub);
+
+        parser.try_parse_vec(vec!(String::from("build"), String::from("--help"))).unwrap();
+        let help = parser.get_subcommand().unwrap().get_help();
+        assert_eq!(help.usage, "prog build [flags] [options]");
+        assert_eq!(help.info, "Builds the project");
+        assert!(help.to_string().ends_with("\nExamples:\n\tprog build --release\n"));
+        assert!(help.to_markdown().contains("## Examples\n\n```\nprog build --release\n```\n"));
+    }
+
+    #[test]
+    fn subcommand_listing() {
+        let mut build = ArgParser::new("build");
+        build.info("Builds the project\nWith more detail");
+        let mut parser = ArgParser::new("prog");
+        parser.subcommand(build).subcommand(ArgParser::new("test"));
+
+        let help = parser.get_help();
+        let commands = help.sections.last().unwrap();
+        assert_eq!(commands.title, "Commands");
+        assert!(help.to_string().ends_with("\nCommands:\n\tbuild          Builds the project\n\ttest\n"));
+
+        let bash = parser.completions(Shell::Bash);
+        assert!(bash.contains("if [ \"$COMP_CWORD\" -eq 1 ]; then\n        COMPREPLY=($(compgen -W '--help -h --version -v build test' -- \"$cur\"))"));
+        assert!(bash.contains("COMPREPLY=($(compgen -W '--help -h --version -v' -- \"$cur\"))\n}"));
+        assert!(parser.completions(Shell::Zsh).contains("'1: :((build\\:Builds\\ the\\ project test\\:))'"));
+        let fish = parser.completions(Shell::Fish);
+        assert!(fish.contains("complete -c prog -f -n __fish_use_subcommand -a 'build' -d 'Builds the project'\n"));
+        assert!(fish.contains("complete -c prog -f -n __fish_use_subcommand -a 'test'\n"));
+    }
+
+    #[test]
+    fn arg_files() {
+        let dir = std::env::temp_dir().join(format!("rargsxd_arg_files_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let outer = dir.join("outer.txt");
+        let inner = dir.join("inner.txt");
+        let looped = dir.join("loop.txt");
+        std::fs::write(&outer, format!("--name 'two words'\n%{}\n", inner.display())).unwrap();
+        std::fs::write(&inner, "--quiet extra").unwrap();
+        std::fs::write(&looped, format!("%{}", looped.display())).unwrap();
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings {
+            arg_file_prefix: Some('%'),
+            ..ParserSettings::default()
+        }).args(vec!(Arg::new("name").option(""), Arg::new("quiet").flag(false)));
+
+        parser.try_parse_vec(vec!(format!("%{}", outer.display()), String::from("--"), String::from("%kept"))).unwrap();
+        assert_eq!(parser.get_option("name").unwrap(), "two words");
+        assert!(parser.get_flag("quiet").unwrap());
+        assert_eq!(parser.extra, vec!("extra", "%kept"));
+
+        parser.reset();
+        let error = parser.try_parse_vec(vec!(format!("%{}", looped.display()))).err().unwrap();
+        assert!(error.message().starts_with("Argument files are nested more than 10 deep"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn raw() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(
+            Arg::new("grep-arg").short('g').expand_env(true).raw(""),
+            Arg::new("quiet").flag(false),
+        ));
+
+        parser.try_parse_vec(vec!("--grep-arg", "--include=*.rs", "--quiet").into_iter().map(String::from).collect()).unwrap();
+        assert_eq!(parser.get_option("grep-arg").unwrap(), "--include=*.rs");
+        assert!(parser.get_flag("quiet").unwrap());
+        parser.reset();
+        parser.try_parse_vec(vec!("-g", "$HOME").into_iter().map(String::from).collect()).unwrap();
+        assert_eq!(parser.get_option("grep-arg").unwrap(), "$HOME");
+        parser.reset();
+        parser.try_parse_vec(vec!("--grep-arg=--x=y").into_iter().map(String::from).collect()).unwrap();
+        assert_eq!(parser.get_option("grep-arg").unwrap(), "--x=y");
+    }
+
+    #[test]
+    fn dump() {
+        std::env::set_var("RARGSXD_DUMP_LEVEL", "3");
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(
+            Arg::new("fast").implies("quiet", "true").flag(false),
+            Arg::new("quiet").flag(false),
+            Arg::new("level").env("RARGSXD_DUMP_LEVEL").option("1"),
+            Arg::new("output").option("out.txt"),
+            Arg::new("tag").repeatable(true).option(""),
+        ));
+        parser.try_parse_vec(vec!("--fast", "--tag", "a", "--tag", "b").into_iter().map(String::from).collect()).unwrap();
+
+        let mut text = Vec::new();
+        parser.dump(&mut text, DumpFormat::Text).unwrap();
+        let text = String::from_utf8(text).unwrap();
+        assert!(text.contains("--fast = true (command line)\n"));
+        assert!(text.contains("--quiet = true (implied)\n"));
+        assert!(text.contains("--level = \"3\" (environment)\n"));
+        assert!(text.contains("--output = \"out.txt\" (default)\n"));
+        assert!(text.contains("--tag = [\"a\", \"b\"] (command line)\n"));
+
+        let mut json = Vec::new();
+        parser.dump(&mut json, DumpFormat::Json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.contains("\"quiet\":{\"value\":true,\"source\":\"implied\"}"));
+        assert!(json.contains("\"tag\":{\"value\":[\"a\",\"b\"],\"source\":\"command line\"}"));
+        assert_eq!(parser.get_arg("level").unwrap().get_source(), ValueSource::Environment);
+    }
+
+    #[test]
+    fn max_term_width() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.max_term_width(40)
+            .args(vec!(Arg::new("format").help("Output format").possible_values(vec!("json", "yaml")).option("json")));
+
+        let help = parser.get_help();
+        assert_eq!(help.width, 40);
+        assert!(help.to_string().contains(&format!("\t-f, --format <FORMAT>  Output format\n\t{0}[default: json]\n\t{0}[possible values: json, yaml]\n", " ".repeat(23))));
+    }
+
+    #[test]
+    fn render_usage() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("input").positional(true).required(true).option("")));
+        assert_eq!(parser.render_usage(), "Usage: program_lol [flags] [options] <INPUT>\n\nFor more information try '--help'");
+    }
+
+    #[test]
+    fn dedup_sorted() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(
+            Arg::new("tag").repeatable(true).dedup(true).option(""),
+            Arg::new("name").repeatable(true).sorted(true).dedup(true).option(""),
+        ));
+
+        parser.try_parse_vec(vec!("--tag", "b", "--tag", "a", "--tag", "b", "--name", "z", "--name", "y", "--name", "z")
+            .into_iter().map(String::from).collect()).unwrap();
+        assert_eq!(parser.get_values("tag").unwrap(), vec!("b", "a"));
+        assert_eq!(parser.get_values("name").unwrap(), vec!("y", "z"));
+        assert_eq!(parser.get_option("name").unwrap(), "y");
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("path").repeatable(true).sorted(true).dedup(true).canonicalize(true).option("")));
+        parser.try_parse_vec(vec!("--path", "src", "--path", "Cargo.toml", "--path", "src")
+            .into_iter().map(String::from).collect()).unwrap();
+        assert_eq!(parser.get_values("path").unwrap(), vec!("Cargo.toml", "src"));
+        let canonical = |path: &str| std::fs::canonicalize(path).unwrap();
+        assert_eq!(parser.get_paths("path").unwrap(), vec!(canonical("Cargo.toml"), canonical("src")));
+    }
+
+    #[test]
+    fn max_extras() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.max_extras(1).args(vec!(Arg::new("quiet").flag(false)));
+        parser.try_parse_vec(vec!("a", "--quiet").into_iter().map(String::from).collect()).unwrap();
+        assert_eq!(parser.extra, vec!("a"));
+
+        parser.reset();
+        let error = parser.try_parse_vec(vec!("a", "--", "b").into_iter().map(String::from).collect()).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::UnknownArgument);
+        assert_eq!(error.message(), "Unexpected extra operand 'b'");
+
+        parser.reset();
+        parser.max_extras(0);
+        assert!(parser.try_parse_vec(vec!(String::from("a"))).is_err());
+    }
+
+    #[test]
+    fn info_first() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings {
+            return_on_info: true,
+            ..ParserSettings::default()
+        }).args(vec!(
+            Arg::new("count").value_type(ValueType::Integer).required(true).option("0"),
+        ));
+
+        parser.try_parse_vec(vec!("--count", "lots", "--bogus", "--help").into_iter().map(String::from).collect()).unwrap();
+        assert_eq!(parser.outcome(), ParseOutcome::HelpPrinted);
+
+        parser.settings(ParserSettings {
+            return_on_info: true,
+            info_in_order: true,
+            ..ParserSettings::default()
+        });
+        let error = parser.try_parse_vec(vec!("--count", "lots", "--help").into_iter().map(String::from).collect()).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn interpolation() {
+        let mut parser = ArgParser::from_argv0();
+        parser.version("1.2.0")
+            .info("{name} {version} converts files")
+            .usage("{name} [options] FILE")
+            .example("{name} --fast a.txt");
+        parser.try_parse_vec_with_bin(vec!(String::from("/usr/bin/convert"))).unwrap();
+
+        let help = parser.get_help();
+        assert_eq!(help.info, "convert 1.2.0 converts files");
+        assert_eq!(help.usage, "convert [options] FILE");
+        assert_eq!(help.examples, vec!("convert --fast a.txt"));
+    }
+
+    #[test]
+    fn meta() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("jobs").meta("category", "performance").meta("stability", "unstable").option("1")));
+        parser.try_parse_vec(Vec::new()).unwrap();
+
+        let arg = parser.get_arg("jobs").unwrap();
+        assert_eq!(arg.get_meta("category"), Some("performance"));
+        assert_eq!(arg.get_meta("missing"), None);
+        assert_eq!(arg.get_all_meta().len(), 2);
+
+        let mut json = Vec::new();
+        parser.dump(&mut json, DumpFormat::Json).unwrap();
+        assert!(String::from_utf8(json).unwrap()
+            .contains("\"jobs\":{\"value\":\"1\",\"source\":\"default\",\"meta\":{\"category\":\"performance\",\"stability\":\"unstable\"}}"));
+    }
+
+    #[test]
+    fn group_help() {
+        let mut parser = ArgParser::new("prog");
+        parser.args(vec!(Arg::new("build").word(WordType::Boolean(false)), Arg::new("quiet").flag(false)))
+            .subcommand(ArgParser::new("test"))
+            .group_help(vec!("Commands", "Words"))
+            .section_title("Words", "Commands");
+
+        let titles: Vec<String> = parser.get_help().sections.into_iter().map(|section| section.title).collect();
+        assert_eq!(titles, vec!("Commands", "Flags"));
+        let help = parser.get_help();
+        let names: Vec<&str> = help.sections[0].entries.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, vec!("test", "build"));
+
+        parser.group_help(vec!("Everything"));
+        assert_eq!(parser.try_parse_vec(Vec::new()).err().unwrap().kind(), ErrorKind::Definition);
+    }
+
+    #[test]
+    fn stateless_parse() {
+        let mut definition = ArgParser::new("program_lol");
+        definition.args(vec!(Arg::new("quiet").flag(false), Arg::new("jobs").value_type(ValueType::Integer).option("1")));
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4).map(|n| {
+                let definition = &definition;
+                scope.spawn(move || parse(definition, &["--jobs", &n.to_string(), "--quiet"]).unwrap())
+            }).collect();
+            for (n, handle) in handles.into_iter().enumerate() {
+                let matches = handle.join().unwrap();
+                assert_eq!(matches.get_option("jobs").unwrap(), n.to_string());
+                assert!(matches.get_flag("quiet").unwrap());
+            }
+        });
+
+        assert!(!definition.get_flag("quiet").unwrap());
+        assert!(parse(&definition, &["--jobs", "many"]).is_err());
+    }
+
+    #[test]
+    fn value_len() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(
+            Arg::new("name").min_len(1).max_len(4).option("x"),
+            Arg::new("tag").max_len(4).len_unit(LengthUnit::Bytes).option(""),
+        ));
+
+        parser.try_parse_vec(vec!("--name", "ゆきだるま", "--tag", "ab").into_iter().map(String::from).collect()).err().unwrap();
+        parser.reset();
+        parser.try_parse_vec(vec!("--name", "ゆきだる").into_iter().map(String::from).collect()).unwrap();
+        let error = parser.try_parse_vec(vec!("--name=").into_iter().map(String::from).collect()).err().unwrap();
+        assert_eq!(error.message(), "Invalid value \"\" for \"--name\": must be at least 1 character long");
+        let error = parser.try_parse_vec(vec!("--tag", "ゆき").into_iter().map(String::from).collect()).err().unwrap();
+        assert_eq!(error.message(), "Invalid value \"ゆき\" for \"--tag\": must be at most 4 bytes long");
+    }
+
+    #[test]
+    fn allow_empty() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("name").allow_empty(false).option("x"), Arg::new("note").option("")));
+
+        parser.try_parse_vec(vec!("--note=", "--name", "a").into_iter().map(String::from).collect()).unwrap();
+        assert_eq!(parser.get_option("note").unwrap(), "");
+        for args in [vec!("--name="), vec!("--name", "")] {
+            let error = parser.try_parse_vec(args.into_iter().map(String::from).collect()).err().unwrap();
+            assert_eq!(error.kind(), ErrorKind::MissingValue);
+            assert_eq!(error.message(), "A value is required for '--name' but none was supplied");
+        }
+    }
+
+    #[test]
+    fn word_shorts() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(
+            Arg::new("build").word(WordType::Boolean(false)),
+            Arg::new("input").positional(true).option(""),
+            Arg::new("quiet").flag(false),
+        ));
+        assert_eq!(parser.get_arg("build").unwrap().short, None);
+        assert_eq!(parser.get_arg("input").unwrap().short, None);
+        assert_eq!(parser.get_arg("quiet").unwrap().short, Some('q'));
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(Arg::new("build").short('b').word(WordType::Boolean(false))));
+        let error = parser.try_parse_vec(Vec::new()).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::Definition);
+        assert_eq!(error.message(), "Argument \"build\" has a short name, which only flags and options that aren't positional can");
+    }
+
+    #[test]
+    fn arg_getters() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings {
+            sort_help: SortOrder::Declaration,
+            ..ParserSettings::default()
+        }).args(vec!(
+            Arg::new("output").short('O').help("Where to write").long_help("Where to write it all").option("out.txt"),
+            Arg::new("quiet").flag(false),
+        ));
+        parser.try_parse_vec(vec!("-O", "a.txt").into_iter().map(String::from).collect()).unwrap();
+
+        let names: Vec<&str> = parser.get_args().into_iter().map(Arg::get_name).collect();
+        assert_eq!(names, vec!("help", "version", "output", "quiet"));
+        let output = parser.get_arg("output").unwrap();
+        assert_eq!(output.get_short(), Some('O'));
+        assert_eq!(output.get_help(), "Where to write");
+        assert_eq!(output.get_long_help(), Some("Where to write it all"));
+        assert_eq!(output.get_type(), &ArgType::option("a.txt"));
+        assert_eq!(output.get_default(), &ArgType::option("out.txt"));
+    }
+
+    #[test]
+    fn arg_kind() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(vec!(
+            Arg::new("quiet").flag(false),
+            Arg::new("output").option(""),
+            Arg::new("pass").raw(""),
+            Arg::new("build").word(WordType::Boolean(false)),
+            Arg::new("input").positional(true).option(""),
+        ));
+
+        let kinds: Vec<(&str, ArgKind)> = parser.get_args().into_iter().map(|arg| (arg.get_name(), arg.get_kind())).collect();
+        assert!(kinds.contains(&("quiet", ArgKind::Flag)));
+        assert!(kinds.contains(&("output", ArgKind::Option)));
+        assert!(kinds.contains(&("pass", ArgKind::Raw)));
+        assert!(kinds.contains(&("build", ArgKind::Word)));
+        assert!(kinds.contains(&("input", ArgKind::Positional)));
+        assert_eq!(Arg::new("untyped").get_kind(), ArgKind::Unknown);
+    }
+
+    #[test]
+    fn advanced() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("quiet").flag(false),
+                    Arg::new("jobs").advanced(true).option("4"),
+                )
+            ).parse_vec(vec!("--jobs".to_string(), "8".to_string()));
+
+        assert_eq!(parser.get_option("jobs").unwrap(), "8");
+        assert!(!parser.get_help().to_string().contains("--jobs"));
+        assert!(!parser.get_long_help().to_string().contains("--jobs"));
+        assert!(parser.get_help_all().to_string().contains("\t-j, --jobs <JOBS>  [default: 4]\n"));
+    }
+
+    #[test]
+    fn docs() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.info("Does things.").args(
+                vec!(
+                    Arg::new("level").help("Level to use").long_help("Level to use.\nHigher is slower.").option("3"),
+                )
+            );
+
+        let markdown = parser.markdown();
+        assert!(markdown.contains("## Options\n\n- `-l, --level <LEVEL>`: Level to use.\n  Higher is slower. [default: 3]\n"));
+        let man = parser.man_page();
+        assert!(man.contains(".SH NAME\nprogram_lol \\- Does things.\n"));
+        assert!(man.contains(".TP\n\\fB\\-l, \\-\\-level <LEVEL>\\fR\nLevel to use.\nHigher is slower. [default: 3]\n"));
+        assert!(parser.get_help().to_string().contains("\t-l, --level <LEVEL>  Level to use [default: 3]\n"));
+    }
+
+    #[test]
+    fn bug_report_url() {
+        let mut sub = ArgParser::new("sub");
+        sub.args(vec!(Arg::new("broken")));
+        let mut parser = ArgParser::new("program_lol");
+        parser.bug_report_url("https://example.com/issues")
+            .args(
+                vec!(
+                    Arg::new("level").option("3"),
+                )
+            )
+            .subcommand(sub);
+
+        assert!(parser.get_help().to_string().ends_with("\nReport bugs at <https://example.com/issues>\n"));
+        let error = parser.try_parse_vec(vec!("sub".to_string())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::Definition);
+        assert_eq!(error.message().matches("Report bugs at <https://example.com/issues>").count(), 1);
+        assert!(error.message().ends_with("\nReport bugs at <https://example.com/issues>"));
+
+        let mut parser = ArgParser::new("program_lol");
+        parser.bug_report_url("https://example.com/issues").args(vec!(Arg::new("count").required(true).option("")));
+        let error = parser.try_parse_vec(Vec::new()).err().unwrap();
+        assert!(!error.message().contains("Report bugs"));
+    }
+
+    #[test]
+    fn license() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.version("1.2.0")
+            .copyright("Copyright (C) 2021 BubbyRoosh")
+            .license("MIT OR Apache-2.0")
+            .homepage("https://example.com/program_lol");
+
+        assert_eq!(parser.version_json(),
+            "{\"name\":\"program_lol\",\"version\":\"1.2.0\",\"authors\":[],\"license\":\"MIT OR Apache-2.0\",\"homepage\":\"https://example.com/program_lol\",\"build\":{}}");
+        let markdown = parser.markdown();
+        assert!(markdown.contains("\n## License\n\nMIT OR Apache-2.0\n\n## Homepage\n\n<https://example.com/program_lol>\n"));
+        let man = parser.man_page();
+        assert!(man.contains(".SH COPYRIGHT\nCopyright (C) 2021 BubbyRoosh\n.br\nLicense: MIT OR Apache\\-2.0\n.br\nHomepage: https://example.com/program_lol\n"));
+    }
+
+    /// Tokens that don't ask for --help/--version (which exit).
+    fn token() -> impl proptest::strategy::Strategy<Value = String> {
+        use proptest::prelude::*;
+
+        prop_oneof!(
+            "--[a-gi-uw-z=_-]{0,8}",
+            "-[a-gi-uw-z0-9=-]{0,4}",
+            "[a-z]{0,6}",
+            "\\PC{0,6}",
+            Just(String::from("--count")),
+            Just(String::from("--size")),
+            Just(String::from("word")),
+        ).prop_filter("exits", |token| !(token.starts_with('-') && (token.contains('h') || token.contains('v'))))
+    }
+
+    /// Parser the property tests parse random tokens with.
+    fn fuzzed_parser(abbreviations: bool, hyphen_values: bool) -> ArgParser {
+        let mut parser = ArgParser::new("program_lol");
+        parser.settings(ParserSettings {
+                abbreviations,
+                allow_hyphen_values: hyphen_values,
+                ..ParserSettings::default()
+            })
+            .args(
+                vec!(
+                    Arg::new("flag").flag(false),
+                    Arg::new("count").short('c').option("0").value_type(ValueType::Integer),
+                    Arg::new("size").option("").num_values(2).value_type(ValueType::Percent),
+                    Arg::new("colour").option("").value_type(ValueType::Color).implies("flag", "yes"),
+                    Arg::new("path").option("").expand_tilde(true).expand_env(true),
+                    Arg::new("word").word(WordType::string("")),
+                    Arg::new("toggle").word(WordType::boolean(false)),
+                )
+            )
+            .group(ArgGroup::new("group").args(["flag", "count"]).multiple(false))
+            .group(ArgGroup::new("sizes").args(["size", "colour"]).multiple(false));
+        parser
+    }
+
+    /// Everything parsing gave: the values, extra arguments and help dialog, or the error.
+    fn snapshot(result: Result<ArgParser, Error>) -> String {
+        match result {
+            Ok(parser) => {
+                let mut dump = Vec::new();
+                parser.dump(&mut dump, DumpFormat::Json).unwrap();
+                format!("{}\n{:?}\n{}", String::from_utf8(dump).unwrap(), parser.extra, parser.get_help())
+            },
+            Err(e) => format!("{:?}: {}", e.kind(), e),
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn parsing_never_panics(args in proptest::collection::vec(token(), 0..8), abbreviations: bool, hyphen_values: bool) {
+            let _ = fuzzed_parser(abbreviations, hyphen_values).try_parse_vec(args);
+        }
+
+        #[test]
+        fn parsing_is_deterministic(args in proptest::collection::vec(token(), 0..8), abbreviations: bool, hyphen_values: bool) {
+            let definition = fuzzed_parser(abbreviations, hyphen_values);
+            let expected = snapshot(fuzzed_parser(abbreviations, hyphen_values).try_parse_vec(args.clone()).cloned());
+
+            proptest::prop_assert_eq!(&snapshot(parse(&definition, &args)), &expected);
+            proptest::prop_assert_eq!(&snapshot(parse(&definition, &args)), &expected);
+
+            let mut parser = definition.clone();
+            let _ = parser.try_parse_vec(args.clone());
+            parser.reset();
+            proptest::prop_assert_eq!(&snapshot(parser.try_parse_vec(args).cloned()), &expected);
+        }
+
+        #[test]
+        fn definitions_never_panic(name: String, short: char, val: String) {
+            proptest::prop_assume!(!name.starts_with('-') && !val.starts_with('-'));
+            let mut parser = ArgParser::new(name.clone());
+            parser.args(
+                    vec!(
+                        Arg::new(&name).short(short).option(&val).implies(&val, &val).requires(&name),
+                    )
+                )
+                .group(ArgGroup::new(&val).arg(&name));
+            let _ = parser.try_parse_vec(vec!(name.clone(), val.clone()));
+        }
+    }
+
+    #[cfg(feature = "glob")]
+    #[test]
+    fn globs() {
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("files").glob(true).repeatable(true).word(WordType::string("")),
+                )
+            ).parse_vec(vec!("files".to_string(), "src/[eg]*.rs".to_string(), "nothing*.rs".to_string()));
+
+        assert_eq!(parser.get_values("files").unwrap(), vec!("src/error.rs", "src/group.rs", "nothing*.rs"));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn datetimes() {
+        use chrono::{Duration, NaiveDate, TimeZone, Utc};
+
+        let args = vec!(
+            "--since".to_string(), "2024-01-01T01:00:00+01:00".to_string(),
+            "--until=2024-02-03".to_string(),
+            "--day=yesterday".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("since").option("now").value_type(ValueType::DateTime),
+                    Arg::new("until").option("now").value_type(ValueType::DateTime),
+                    Arg::new("day").option("today").value_type(ValueType::Date),
+                )
+            ).parse_vec(args);
+
+        assert_eq!(parser.get_datetime("since").unwrap(), Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(parser.get_datetime("until").unwrap(), Utc.with_ymd_and_hms(2024, 2, 3, 0, 0, 0).unwrap());
+        assert_eq!(parser.get_date("day").unwrap(), Utc::now().date_naive() - Duration::days(1));
+        assert_eq!(value::parse_date("2024-01-01T12:00:00Z").unwrap(), NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert!(value::parse_datetime("2024-13-01").is_err());
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn urls() {
+        let args = vec!(
+            "--endpoint".to_string(), "https://api.example.com/v1".to_string(),
+        );
+        let mut parser = ArgParser::new("program_lol");
+        parser.args(
+                vec!(
+                    Arg::new("endpoint").option("").value_type(ValueType::Url),
+                )
+            ).parse_vec(args);
+
+        let url = parser.get_url("endpoint").unwrap();
+        assert_eq!(url.host_str().unwrap(), "api.example.com");
+        assert_eq!(url.path(), "/v1");
+        assert!(value::parse_url("not a url").is_err());
+    }
 }
@@ -0,0 +1,50 @@
+/// Name of the crate from Cargo.toml (`CARGO_PKG_NAME`) as a &'static str.
+#[macro_export]
+macro_rules! crate_name {
+    () => {
+        env!("CARGO_PKG_NAME")
+    };
+}
+
+/// Version of the crate from Cargo.toml (`CARGO_PKG_VERSION`) as a &'static str.
+#[macro_export]
+macro_rules! crate_version {
+    () => {
+        env!("CARGO_PKG_VERSION")
+    };
+}
+
+/// Authors of the crate from Cargo.toml (`CARGO_PKG_AUTHORS`) as a String, separated by ", "
+/// or by the given separator.
+#[macro_export]
+macro_rules! crate_authors {
+    () => {
+        $crate::crate_authors!(", ")
+    };
+    ($sep:expr) => {
+        env!("CARGO_PKG_AUTHORS").replace(':', $sep)
+    };
+}
+
+/// Description of the crate from Cargo.toml (`CARGO_PKG_DESCRIPTION`) as a &'static str.
+#[macro_export]
+macro_rules! crate_description {
+    () => {
+        env!("CARGO_PKG_DESCRIPTION")
+    };
+}
+
+/// ArgParser with the name, version, author and info from Cargo.toml (`CARGO_PKG_*`).
+///
+/// This is a macro rather than an ArgParser constructor since a function in this crate would
+/// read the metadata of rargsxd instead of the crate calling it.
+#[macro_export]
+macro_rules! crate_parser {
+    () => {{
+        let mut parser = $crate::ArgParser::new($crate::crate_name!());
+        parser.version($crate::crate_version!())
+            .author($crate::crate_authors!())
+            .info($crate::crate_description!());
+        parser
+    }};
+}
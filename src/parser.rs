@@ -1,158 +1,1024 @@
 use super::argument::*;
+use super::completions::{self, Shell};
+use super::error::{Error, ErrorKind};
+use super::group::ArgGroup;
+use super::help::{Help, HelpEntry, HelpSection};
+use super::quote::{shell_split, Quoting};
+use super::settings::{ColorChoice, ParserSettings, SortOrder};
+use super::value::{self, FromArgValue};
 
 use std::{env, process};
+use std::borrow::Cow;
+use std::io::{IsTerminal, Write};
 use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+/// Prints a parse trace line to stderr if `$debug` is set, see ParserSettings::debug.
+macro_rules! trace {
+    ($debug:expr, $($arg:tt)*) => {
+        if $debug {
+            eprintln!("[rargsxd] {}", format_args!($($arg)*));
+        }
+    };
+}
+
+/// Sections of the help dialog in their default order (ArgParser::group_help).
+const HELP_SECTIONS: [&str; 5] = ["Arguments", "Flags", "Options", "Words", "Commands"];
+
+/// How deep argument files can include other argument files (ParserSettings::arg_file_prefix).
+const ARG_FILE_DEPTH: usize = 10;
+
+/// Hook ran with the raw arguments before parsing, see ArgParser::before_parse.
+type BeforeHook = Arc<dyn Fn(&mut Vec<String>) -> Result<(), String> + Send + Sync>;
+/// Hook ran with the parser after parsing, see ArgParser::after_parse.
+type AfterHook = Arc<dyn Fn(&ArgParser) -> Result<(), String> + Send + Sync>;
+
+/// What parsing ended with, see ArgParser::outcome.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParseOutcome {
+    /// The arguments were parsed.
+    Parsed,
+    /// The help dialog (or a help topic) was printed instead, with ParserSettings::return_on_info.
+    HelpPrinted,
+    /// The version was printed instead, with ParserSettings::return_on_info.
+    VersionPrinted,
+    /// An Arg::short_circuit callback ran instead, with ParserSettings::return_on_info.
+    ShortCircuited,
+}
+
+/// Format of ArgParser::dump.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DumpFormat {
+    /// `--name = "value" (source)` lines.
+    Text,
+    /// An object of `"name": {"value": ..., "source": "..."}`, with `"meta"` for Arg::meta.
+    Json,
+}
 
 /// Main parser struct.
+#[derive(Clone)]
 pub struct ArgParser {
     /// Name of the program.
-    name: String,
+    name: Cow<'static, str>,
+    /// Whether or not to take the name from argv[0] when parsing std::env::args_os().
+    name_from_argv0: bool,
     /// Name of the author.
-    author: String,
+    author: Cow<'static, str>,
     /// Version of the program.
-    version: String,
+    version: Cow<'static, str>,
     /// Copyright (if any)
-    copyright: String,
+    copyright: Cow<'static, str>,
+    /// SPDX license expression (if any)
+    license: Cow<'static, str>,
+    /// Homepage URL (if any)
+    homepage: Cow<'static, str>,
+    /// Where to report bugs, shown after the help dialog and definition errors (if any).
+    bug_report_url: Option<Cow<'static, str>>,
+    /// Where help and version info go instead of stdout, for the testing module.
+    output: Option<Arc<Mutex<String>>>,
     /// Description/info on the program.
-    info: String,
-    /// Usage (defaults to "{} [flags] [options]", name)
-    usage: String,
+    info: Cow<'static, str>,
+    /// Usage set with ArgParser::usage, None for the default ("{} [flags] [options]", name)
+    usage: Option<Cow<'static, str>>,
+    /// Example commands listed after the arguments in the help dialog.
+    examples: Vec<Cow<'static, str>>,
+    /// Names of the parsers this one is a subcommand of ("prog" for `prog sub`), if any.
+    parent_path: Option<String>,
+    /// Arguments by name. Up to 11 fit in one B-tree node, so small parsers look names up
+    /// with a linear scan and never hash (benches/tiny.rs measures it).
     args: BTreeMap<String, Arg>,
+    groups: BTreeMap<String, ArgGroup>,
+    /// Arguments that weren't matched (and everything after `--`), in order.
     pub extra: Vec<String>,
-    /// Prints help and exits if no args are passed when parsing.
-    require_args: bool,
+    /// ArgParser::extra as they were passed (before being lossily converted).
+    pub extra_os: Vec<OsString>,
+    /// Dash arguments that weren't registered, with ParserSettings::collect_unknown.
+    pub unknown_flags: Vec<String>,
+    /// Long arguments that weren't registered (and the value after them, if any), in order,
+    /// with ParserSettings::forward_unknown.
+    pub forwarded: Vec<String>,
+    /// Index in ArgParser::extra of the first argument after `--` (if any).
+    trailing_start: Option<usize>,
+    /// Behavior toggles.
+    settings: ParserSettings,
+    /// Whether or not to use the parent's settings when parsed as a subcommand.
+    inherit_settings: bool,
+    /// Settings set with their own builders (ArgParser::sort_help, ArgParser::require_args),
+    /// kept by ArgParser::settings and over inherited settings.
+    own_settings: Vec<&'static str>,
+    subcommands: BTreeMap<String, ArgParser>,
+    /// Name of the subcommand found when parsing (if any).
+    subcommand: Option<String>,
+    /// What the last parse ended with.
+    outcome: ParseOutcome,
+    before_parse: Vec<BeforeHook>,
+    after_parse: Vec<AfterHook>,
+    /// Cross-argument checks ran before required arguments are checked (ArgParser::check).
+    checks: Vec<AfterHook>,
+    /// Errors setting up the parser, returned when parsing.
+    errors: Vec<Error>,
+    /// Old names of arguments (Arg::replaced_by) to their new names.
+    renamed: BTreeMap<String, String>,
+    /// Long-form help shown by `prog help <topic>` or `--help=<topic>`.
+    topics: BTreeMap<String, Cow<'static, str>>,
+    /// Extra fields for `--version=json` (ArgParser::build_info).
+    build_info: BTreeMap<String, String>,
+    /// Most columns the help dialog is wrapped at (ArgParser::max_term_width).
+    max_term_width: Option<usize>,
+    /// Most arguments ArgParser::extra can collect (ArgParser::max_extras).
+    max_extras: Option<usize>,
+    /// Help sections listed first, in order (ArgParser::group_help).
+    section_order: Vec<String>,
+    /// Titles help sections are shown with instead of their names (ArgParser::section_title).
+    section_titles: BTreeMap<String, String>,
 }
 
 impl ArgParser {
     /// Parses std::env::args().
     pub fn parse(&mut self) -> &mut Self {
-        let mut args: Vec<_> = env::args().collect();
-        args.remove(0);
-        self.parse_vec(args);
+        let error = self.try_parse().err();
+        if let Some(e) = error {
+            self.error_exit(e.message());
+        }
         self
     }
 
-    /// Parses a given Vec<String>.
+    /// Parses a given `Vec<String>`.
     pub fn parse_vec(&mut self, args: Vec<String>) -> &mut Self {
-        if args.len() == 0 && self.require_args {
+        let error = self.try_parse_vec(args).err();
+        if let Some(e) = error {
+            self.error_exit(e.message());
+        }
+        self
+    }
+
+    /// Parses a given `Vec<String>` whose first element is `argv[0]` (`env::args().collect()`),
+    /// which names the parser like ArgParser::parse does.
+    pub fn parse_vec_with_bin(&mut self, args: Vec<String>) -> &mut Self {
+        let error = self.try_parse_vec_with_bin(args).err();
+        if let Some(e) = error {
+            self.error_exit(e.message());
+        }
+        self
+    }
+
+    /// Parses a given `Vec<String>` whose first element is `argv[0]`, returning an Error instead
+    /// of exiting if parsing fails.
+    pub fn try_parse_vec_with_bin(&mut self, args: Vec<String>) -> Result<&mut Self, Error> {
+        self.try_parse_argv(args.into_iter().map(OsString::from).collect())
+    }
+
+    /// Parses `line` split into arguments with the quoting rules of the platform's shell
+    /// (Quoting::native), for commands read as one string.
+    pub fn parse_str(&mut self, line: &str) -> &mut Self {
+        let error = self.try_parse_str(line, Quoting::native()).err();
+        if let Some(e) = error {
+            self.error_exit(e.message());
+        }
+        self
+    }
+
+    /// Parses `line` split into arguments with `quoting`'s rules (shell_split), returning an
+    /// Error instead of exiting if it can't be split or parsing fails.
+    pub fn try_parse_str(&mut self, line: &str, quoting: Quoting) -> Result<&mut Self, Error> {
+        let args = shell_split(line, quoting)
+            .map_err(|e| Error::new(ErrorKind::InvalidValue, &format!("Couldn't split {:?}: {}", line, e)))?;
+        self.try_parse_vec(args)
+    }
+
+    /// Puts every argument (and subcommand) back to its default and clears ArgParser::extra,
+    /// so the parser can parse again without being rebuilt. Defaults are stored apart from
+    /// parsed values, so nothing is cloned, and value buffers are kept for reuse.
+    pub fn reset(&mut self) -> &mut Self {
+        for arg in self.args.values_mut() {
+            arg.reset();
+        }
+        for sub in self.subcommands.values_mut() {
+            sub.reset();
+        }
+        self.extra.clear();
+        self.extra_os.clear();
+        self.unknown_flags.clear();
+        self.forwarded.clear();
+        self.trailing_start = None;
+        self.subcommand = None;
+        self.outcome = ParseOutcome::Parsed;
+        self
+    }
+
+    /// Parses a given `Vec<OsString>`.
+    pub fn parse_os_vec(&mut self, args: Vec<OsString>) -> &mut Self {
+        let error = self.try_parse_os_vec(args).err();
+        if let Some(e) = error {
+            self.error_exit(e.message());
+        }
+        self
+    }
+
+    /// Parses std::env::args_os(), returning an Error instead of exiting if parsing fails.
+    pub fn try_parse(&mut self) -> Result<&mut Self, Error> {
+        self.try_parse_argv(env::args_os().collect())
+    }
+
+    /// Parses `argv` including argv[0] (ArgParser::from_argv0, ParserSettings::multicall).
+    pub (crate) fn try_parse_argv(&mut self, mut argv: Vec<OsString>) -> Result<&mut Self, Error> {
+        if argv.is_empty() {
+            return self.try_parse_os_vec(argv);
+        }
+
+        let argv0 = argv.remove(0);
+        self.apply_argv0(&argv0);
+
+        if self.settings.multicall {
+            let applet = Path::new(&argv0).file_stem().map(|stem| stem.to_string_lossy().into_owned());
+            if let Some(applet) = applet.filter(|applet| self.subcommands.contains_key(applet)) {
+                if let Some(e) = self.errors.first() {
+                    return Err(self.bug_report(e.clone()));
+                }
+                self.parse_subcommand(&applet, argv)?;
+                return Ok(self);
+            }
+        }
+        self.try_parse_os_vec(argv)
+    }
+
+    /// Parses a given `Vec<String>`, returning an Error instead of exiting if parsing fails.
+    ///
+    /// --help, --version and Arg::short_circuit arguments exit, or return with
+    /// ParserSettings::return_on_info (see ArgParser::outcome).
+    ///
+    /// Once the arguments are parsed they're checked in this order, returning the first error:
+    /// Arg::requires and Arg::conflicts, ArgGroup constraints, Arg::validator, ArgParser::check,
+    /// then required arguments (all of them in one error). Arguments and groups are checked in the
+    /// order they were given to the parser, so the same arguments always give the same error.
+    pub fn try_parse_vec(&mut self, args: Vec<String>) -> Result<&mut Self, Error> {
+        self.try_parse_os_vec(args.into_iter().map(OsString::from).collect())
+    }
+
+    /// Parses a given `Vec<OsString>`, returning an Error instead of exiting if parsing fails.
+    ///
+    /// Arguments that aren't valid UTF-8 are matched lossily, but ValueType::String_ and
+    /// ValueType::Path values and ArgParser::extra_os keep them as they were passed.
+    pub fn try_parse_os_vec(&mut self, os_args: Vec<OsString>) -> Result<&mut Self, Error> {
+        match self.parse_os_args(os_args) {
+            Ok(_) => Ok(self),
+            Err(e) => Err(self.bug_report(e)),
+        }
+    }
+
+    /// Adds the ArgParser::bug_report_url footer to ErrorKind::Definition errors that don't
+    /// have it yet (from a subcommand).
+    fn bug_report(&self, error: Error) -> Error {
+        let url = match &self.bug_report_url {
+            Some(url) if error.kind() == ErrorKind::Definition => url,
+            _ => return error,
+        };
+        let footer = format!("Report bugs at <{}>", url);
+        if error.message().ends_with(&footer) {
+            return error;
+        }
+        Error::new(error.kind(), &format!("{}\n{}", error.message(), footer))
+    }
+
+    /// Parses `os_args`, see ArgParser::try_parse_os_vec.
+    fn parse_os_args(&mut self, os_args: Vec<OsString>) -> Result<&mut Self, Error> {
+        if let Some(e) = self.errors.first() {
+            return Err(e.clone());
+        }
+        self.validate_positionals()?;
+        self.outcome = ParseOutcome::Parsed;
+        let os_args = match self.settings.arg_file_prefix {
+            Some(prefix) => expand_arg_files(os_args, prefix, 0)?,
+            None => os_args,
+        };
+
+        let mut args: Vec<String> = os_args.iter().map(|arg| arg.to_string_lossy().into_owned()).collect();
+        // Hooks only see lossy Strings (and can reorder, add or remove them), so the originals
+        // are looked back up by their lossy String afterwards.
+        let originals: BTreeMap<String, OsString> = args.iter().cloned().zip(os_args).collect();
+        for hook in self.before_parse.clone() {
+            hook(&mut args).map_err(|e| Error::new(ErrorKind::Custom, &e))?;
+        }
+
+        let mut os_args: Vec<OsString> = args.iter()
+            .map(|arg| originals.get(arg).map_or_else(|| OsString::from(arg), OsString::clone))
+            .collect();
+
+        if self.settings.single_dash_long {
+            for (arg, os_arg) in args.iter_mut().zip(os_args.iter_mut()) {
+                if let Some(long) = self.single_dash_long(arg) {
+                    // Only lossy if `-Xvalue` had an invalid UTF-8 value.
+                    *os_arg = if long == format!("-{}", arg) {
+                        let mut dashed = OsString::from("-");
+                        dashed.push(&*os_arg);
+                        dashed
+                    } else {
+                        OsString::from(&long)
+                    };
+                    *arg = long;
+                }
+            }
+        }
+
+        // Help and version come before any error in the other arguments.
+        if let Some(idx) = self.info_request(&args).filter(|_| !self.settings.info_in_order) {
+            args = vec!(args.swap_remove(idx));
+            os_args = vec!(os_args.swap_remove(idx));
+        }
+
+        if args.is_empty() && self.settings.require_args {
             self.print_help();
-            process::exit(1);
+            return self.info_printed(ParseOutcome::HelpPrinted, self.settings.exit_code);
         }
 
+        let debug = self.debugging();
+        trace!(debug, "parsing {} {:?}", self.name, args);
+
+        let hyphen_values = self.settings.allow_hyphen_values;
         let mut skip_indexes = Vec::new();
+        let mut options_ended = false;
+        // Where repeatable words stop taking values.
+        let names: Vec<String> = self.args.keys().chain(self.subcommands.keys()).cloned().collect();
+        let positionals: Vec<String> = self.sorted_positionals().iter().map(|arg| arg.name.clone()).collect();
+        let mut next_positional = 0;
+        let trailing_var_arg = self.settings.trailing_var_arg;
+        let allow_missing_positional = self.settings.allow_missing_positional;
+        // Positional values given with ParserSettings::allow_missing_positional, assigned once
+        // it's known how many there are.
+        let mut pending = Vec::new();
+        // For ParserSettings::options_first.
+        let mut seen_operand = false;
         for (idx, arg) in args.iter().enumerate() {
-            if let Some(arg) = self.args.get_mut(arg) {
-                if let ArgType::Word(w) = arg.clone().typ {
+            // Already used as a value.
+            if skip_indexes.contains(&idx) {continue}
+
+            if options_ended {
+                let last = self.sorted_positionals().into_iter().find(|arg| arg.last).map(|arg| arg.name.clone());
+                if let Some(last) = last {
+                    trace!(debug, "#{} {:?}: after the end of options, \"{}\" takes the rest", idx, arg, last);
+                    self.take_rest(&last, &args, &os_args, idx)?;
+                    break;
+                }
+                trace!(debug, "#{} {:?}: after the end of options, added to extra", idx, arg);
+                self.push_extra(arg, &os_args[idx])?;
+                continue;
+            } else if arg == "--" {
+                trace!(debug, "#{} \"--\": end of options", idx);
+                options_ended = true;
+                self.trailing_start = Some(self.extra.len());
+                continue;
+            }
+
+            if seen_operand && self.settings.options_first && arg.starts_with('-') && arg != "-" {
+                return Err(Error::new(ErrorKind::UnknownArgument,
+                    &format!("Options must come before file operands: {}", arg)));
+            }
+
+            if arg == "help" && !self.topics.is_empty() && !self.subcommands.contains_key(arg) {
+                match args.get(idx + 1) {
+                    Some(topic) => self.print_topic(topic)?,
+                    None => self.print_long_help(),
+                }
+                return self.info_printed(ParseOutcome::HelpPrinted, 1);
+            }
+
+            if let Some(arg) = self.args.get_mut(arg).filter(|arg| matches!(arg.default, ArgType::Word(_))) {
+                if let ArgType::Word(w) = arg.current().clone() {
+                    let skipped = skip_indexes.len();
                     match w {
-                        WordType::Boolean(boolean) => {arg.word(WordType::Boolean(!boolean));},
+                        WordType::Boolean(boolean) => {
+                            trace!(debug, "#{} {:?}: boolean word, set to {}", idx, arg.name, !boolean);
+                            arg.parsed = Some(ArgType::Word(WordType::Boolean(!boolean)));
+                            arg.set();
+                        },
+                        WordType::String_(_) if arg.repeatable => {
+                            let values: Vec<&str> = args.iter()
+                                .skip(idx + 1)
+                                .take_while(|next| (hyphen_values || !next.starts_with('-')) && !names.contains(next))
+                                .map(String::as_str)
+                                .collect();
+                            trace!(debug, "#{} {:?}: repeatable word, takes {:?}", idx, arg.name, values);
+                            skip_indexes.extend(idx + 1..=idx + values.len());
+                            arg.set_os_values(&values, &os_args[idx + 1..=idx + values.len()])?;
+                        },
                         WordType::String_(_) => {
                             let next = args.get(idx + 1);
                             if let Some(next) = next {
-                                if !next.starts_with('-') {
-                                    arg.word(WordType::String_(next.clone()));
-                                    arg.set();
+                                if hyphen_values || !next.starts_with('-') {
+                                    trace!(debug, "#{} {:?}: string word, takes {:?}", idx, arg.name, next);
+                                    arg.set_os_values(&[next], &os_args[idx + 1..=idx + 1])?;
                                     skip_indexes.push(idx + 1);
+                                } else {
+                                    trace!(debug, "#{} {:?}: string word, not taking {:?} (starts with -)", idx, arg.name, next);
                                 }
+                            } else {
+                                trace!(debug, "#{} {:?}: string word, no value after it", idx, arg.name);
                             }
                         },
                     }
+                    arg.occurred(idx, &args[idx..=idx + skip_indexes.len() - skipped]);
                 }
                 continue;
             } else if let Some(arg) = arg.strip_prefix("--") {
-                if arg == "help" {self.help_exit()}
-                else if arg == "version" {self.version_exit()}
-
-                if let Some(arg) = self.args.get_mut(arg) {
-                    match arg.typ {
-                        ArgType::Flag(boolean) => {arg.flag(!boolean);arg.set();},
-                        ArgType::Option_(_) => {
-                            if let Some(next) = args.get(idx + 1) {
-                                if !next.starts_with('-') {
-                                    arg.option(&next);
-                                    arg.set();
-                                    skip_indexes.push(idx + 1);
-                                }
-                            }
-                        },
-                        _ => {},
+                if self.settings.posix {
+                    return Err(Error::new(ErrorKind::UnknownArgument,
+                        &format!("Long arguments aren't allowed: --{}", arg)));
+                }
+
+                // --name=value
+                let (arg, value) = match arg.split_once('=') {
+                    Some((name, value)) => (name, Some(value)),
+                    None => (arg, None),
+                };
+                let arg = self.long_name(arg)?;
+                let arg = arg.as_str();
+                trace!(debug, "#{} {:?}: long argument \"--{}\"{}", idx, args[idx], arg,
+                    value.map(|value| format!(" with value {:?}", value)).unwrap_or_default());
+
+                if arg == "help" {
+                    match value {
+                        Some(topic) => self.print_topic(topic)?,
+                        None => self.print_long_help(),
                     }
-                } else {
-                    self.unexpected(&format!("--{}", arg));
-                }
-
-            } else if let Some(arg) = arg.strip_prefix('-') {
-                arg.chars().into_iter().for_each(|ch| {
-                    if ch == 'h' {self.help_exit()}
-                    else if ch == 'v' {self.version_exit()}
-
-                    for arg in self.args.values_mut() {
-                        if arg.short == ch {
-                            match arg.typ {
-                                ArgType::Flag(boolean) => {arg.flag(!boolean);arg.set();},
-                                ArgType::Option_(_) => {
-                                    if let Some(next) = args.get(idx + 1) {
-                                        if !next.starts_with('-') {
-                                            arg.option(&next);
-                                            arg.set();
-                                            skip_indexes.push(idx + 1);
-                                        } else {
-                                            println!("{}", arg.name);
-                                            // Couldn't do this here because multiple mutable calls
-                                            // to self
-                                            //self.unexpected(next);
-
-                                            eprintln!("Unexpected argument: \"{}\"", next);
-                                            self.print_help();
-                                            process::exit(1);
-                                        }
+                    return self.info_printed(ParseOutcome::HelpPrinted, 1);
+                }
+                else if arg == "help-all" && !self.args.contains_key(arg) {
+                    self.print_help_all();
+                    return self.info_printed(ParseOutcome::HelpPrinted, 1);
+                }
+                else if arg == "version" {
+                    match value {
+                        Some("json") => self.print_out(&format!("{}\n", self.version_json())),
+                        Some(format) => return Err(Error::new(ErrorKind::InvalidValue,
+                            &format!("Invalid value \"{}\" for \"--version\": expected json", format))),
+                        None => self.print_version(),
+                    }
+                    return self.info_printed(ParseOutcome::VersionPrinted, 1);
+                }
+
+                if let Some(new) = self.renamed.get(arg) {
+                    return Err(Error::new(ErrorKind::UnknownArgument, &format!("'--{}' was renamed to '--{}'", arg, new)));
+                }
+
+                let known = self.args.get(arg).is_some_and(Arg::is_named);
+                if !known && self.settings.collect_unknown {
+                    trace!(debug, "#{} {:?}: unknown, added to unknown_flags", idx, args[idx]);
+                    self.unknown_flags.push(args[idx].clone());
+                    continue;
+                } else if !known && self.settings.forward_unknown {
+                    if self.settings.warn_forwarded {
+                        self.print_err(&format!("warning: ignoring unknown argument \"{}\"\n", args[idx]));
+                    }
+                    self.forwarded.push(args[idx].clone());
+                    let next = args.get(idx + 1).filter(|next| value.is_none()
+                        && !next.starts_with('-') && !names.contains(next));
+                    if let Some(next) = next {
+                        self.forwarded.push(next.clone());
+                        skip_indexes.push(idx + 1);
+                    }
+                    trace!(debug, "#{} {:?}: unknown, forwarded{}", idx, args[idx],
+                        next.map(|next| format!(" with {:?}", next)).unwrap_or_default());
+                    continue;
+                } else if !known {
+                    return Err(self.unknown_argument(&format!("--{}", arg)));
+                }
+
+                let arg = match self.args.get_mut(arg) {
+                    Some(arg) => arg,
+                    None => return Err(unexpected(&format!("--{}", arg))),
+                };
+                let skipped = skip_indexes.len();
+
+                match (arg.current(), value) {
+                    (ArgType::Flag(_), Some(value)) => arg.set_flag(value)?,
+                    (ArgType::Flag(boolean), None) => {arg.parsed = Some(ArgType::Flag(arg.plus_toggle || !boolean));arg.set();},
+                    _ => {
+                        match option_values(arg, &args, idx, value, hyphen_values) {
+                            Some(values) => {
+                                trace!(debug, "#{} {:?}: option takes {:?}", idx, args[idx], values);
+                                let consumed = values.len() - value.iter().count();
+                                skip_indexes.extend(idx + 1..=idx + consumed);
+
+                                let mut raw: Vec<OsString> = value.map(|_| after_equals(&os_args[idx])).into_iter().collect();
+                                raw.extend_from_slice(&os_args[idx + 1..=idx + consumed]);
+                                arg.set_os_values(&values, &raw)?;
+                            },
+                            None if arg.nargs != Nargs::Exactly(1) => {
+                                return Err(Error::new(ErrorKind::MissingValue,
+                                    &format!("\"{}\" takes {}", arg.display_name(), arg.nargs)));
+                            },
+                            None => {
+                                trace!(debug, "#{} {:?}: option takes no value (none left or starts with -)", idx, args[idx]);
+                            },
+                        }
+                    },
+                }
+                arg.occurred(idx, &args[idx..=idx + skip_indexes.len() - skipped]);
+            } else if let Some(digits) = arg.strip_prefix('-')
+                .filter(|digits| is_digits(digits) && self.args.values().any(|arg| arg.digit_shorts)) {
+                let matches: Vec<String> = self.args.values().filter(|arg| arg.digit_shorts).map(Arg::display_name).collect();
+                if matches.len() > 1 {
+                    return Err(Error::new(ErrorKind::Ambiguous,
+                        &format!("-{} matches more than one argument: {}", digits, matches.join(", "))));
+                }
+
+                if let Some(arg) = self.args.values_mut().find(|arg| arg.digit_shorts) {
+                    trace!(debug, "#{} {:?}: sets \"{}\" to {}", idx, args[idx], arg.display_name(), digits);
+                    arg.set_values(&[digits])?;
+                    arg.occurred(idx, &args[idx..=idx]);
+                }
+            } else if let Some(arg) = arg.strip_prefix('-').filter(|shorts| !shorts.is_empty()) {
+                // -o=value, for the last short. A lone `-` (stdin) is an operand.
+                let (arg, value) = match arg.split_once('=') {
+                    Some((shorts, value)) => (shorts, Some(value)),
+                    None => (arg, None),
+                };
+                let shorts = graphemes(arg);
+                trace!(debug, "#{} {:?}: short arguments {:?}{}", idx, args[idx], shorts,
+                    value.map(|value| format!(" with value {:?}", value)).unwrap_or_default());
+                if arg == "hh" {
+                    self.print_help_all();
+                    return self.info_printed(ParseOutcome::HelpPrinted, 1);
+                }
+                if shorts.is_empty() && value.is_some() {
+                    return Err(unexpected(&args[idx]));
+                }
+                if self.settings.single_dash_long && shorts.len() > 1 {
+                    return Err(unexpected(&args[idx]));
+                }
+
+                let unknown = shorts.iter().any(|short| !self.args.values()
+                    .any(|arg| short.chars().eq(arg.short) && arg.is_named()));
+                if unknown && self.settings.collect_unknown {
+                    trace!(debug, "#{} {:?}: unknown, added to unknown_flags", idx, args[idx]);
+                    self.unknown_flags.push(args[idx].clone());
+                    continue;
+                }
+
+                // Each short that takes a value takes the next one not yet taken (`-fo in out`).
+                let mut taken = idx;
+                let last = shorts.len().saturating_sub(1);
+                for (short_idx, short) in shorts.iter().enumerate() {
+                    let value = value.filter(|_| short_idx == last);
+
+                    // Shorts are single chars, so a char with combining marks never matches.
+                    let mut chars = short.chars();
+                    let ch = match (chars.next(), chars.next()) {
+                        (Some(ch), None) => ch,
+                        _ => return Err(unexpected(&format!("-{}", short))),
+                    };
+
+                    if ch == 'W' && self.settings.posix {
+                        return Err(Error::new(ErrorKind::UnknownArgument,
+                            "-W is reserved by POSIX for implementation extensions"));
+                    }
+
+                    if ch == 'h' {
+                        self.print_help();
+                        return self.info_printed(ParseOutcome::HelpPrinted, 1);
+                    } else if ch == 'v' {
+                        self.print_version();
+                        return self.info_printed(ParseOutcome::VersionPrinted, 1);
+                    }
+
+                    let is_match = |arg: &Arg| arg.short == Some(ch) && arg.is_named();
+                    let matches: Vec<String> = self.args.values().filter(|arg| is_match(arg)).map(Arg::display_name).collect();
+                    if matches.len() > 1 {
+                        return Err(Error::new(ErrorKind::Ambiguous,
+                            &format!("-{} matches more than one argument: {}", ch, matches.join(", "))));
+                    }
+
+                    let arg = match self.args.values_mut().find(|arg| is_match(arg)) {
+                        Some(arg) => arg,
+                        None => return Err(unexpected(&format!("-{}", ch))),
+                    };
+                    let first = taken;
+
+                    trace!(debug, "#{} -{}: matches \"{}\"", idx, ch, arg.display_name());
+                    match (arg.current(), value) {
+                        (ArgType::Flag(_), Some(value)) => arg.set_flag(value)?,
+                        (ArgType::Flag(boolean), None) => {arg.parsed = Some(ArgType::Flag(arg.plus_toggle || !boolean));arg.set();},
+                        _ => {
+                            match option_values(arg, &args, taken, value, hyphen_values) {
+                                Some(values) => {
+                                    trace!(debug, "#{} -{}: option takes {:?}", idx, ch, values);
+                                    let consumed = values.len() - value.iter().count();
+                                    skip_indexes.extend(taken + 1..=taken + consumed);
+
+                                    let mut raw: Vec<OsString> = value.map(|_| after_equals(&os_args[idx])).into_iter().collect();
+                                    raw.extend_from_slice(&os_args[taken + 1..=taken + consumed]);
+                                    arg.set_os_values(&values, &raw)?;
+                                    taken += consumed;
+                                },
+                                None if arg.nargs != Nargs::Exactly(1) => {
+                                    return Err(Error::new(ErrorKind::MissingValue,
+                                        &format!("\"-{}\" takes {}", ch, arg.nargs)));
+                                },
+                                None => {
+                                    if let Some(next) = args.get(taken + 1) {
+                                        return Err(unexpected(next));
                                     }
                                 },
-                                _ => {},
                             }
-                        }
+                        },
                     }
-                });
-            } else {
-                if !skip_indexes.contains(&idx) {
-                    self.extra.push(String::from(arg));
+                    let mut tokens = vec!(args[idx].clone());
+                    tokens.extend_from_slice(&args[first + 1..=taken]);
+                    arg.occurred(idx, &tokens);
+                }
+            } else if let Some(shorts) = arg.strip_prefix('+').filter(|shorts| !shorts.is_empty() && shorts.chars()
+                .all(|ch| self.args.values().any(|arg| arg.plus_toggle && arg.short == Some(ch)))) {
+                // +x turns off Arg::plus_toggle flags, like `set +x`.
+                for ch in shorts.chars() {
+                    if let Some(arg) = self.args.values_mut().find(|arg| arg.plus_toggle && arg.short == Some(ch)) {
+                        trace!(debug, "#{} +{}: turns off \"{}\"", idx, ch, arg.display_name());
+                        arg.parsed = Some(ArgType::Flag(false));
+                        arg.set();
+                        arg.occurred(idx, &args[idx..=idx]);
+                    }
+                }
+            } else if self.subcommands.contains_key(arg) {
+                trace!(debug, "#{} {:?}: subcommand, parsing the rest with it", idx, arg);
+                self.parse_subcommand(arg, os_args[idx + 1..].to_vec())?;
+                if self.outcome != ParseOutcome::Parsed {
+                    return Ok(self);
+                }
+                break;
+            } else if let Some(positional) = positionals.get(next_positional).and_then(|name| self.args.get_mut(name)) {
+                if positional.last || (trailing_var_arg && next_positional + 1 == positionals.len()) {
+                    let name = positional.name.clone();
+                    trace!(debug, "#{} {:?}: \"{}\" takes the rest", idx, arg, name);
+                    self.take_rest(&name, &args, &os_args, idx)?;
+                    break;
+                }
+
+                if allow_missing_positional {
+                    trace!(debug, "#{} {:?}: positional value, assigned after parsing", idx, arg);
+                    pending.push(idx);
+                } else {
+                    trace!(debug, "#{} {:?}: value of positional \"{}\"", idx, arg, positional.name);
+                    positional.set_os_values(&[arg], &os_args[idx..=idx])?;
+                    positional.occurred(idx, &args[idx..=idx]);
                 }
+                if !positional.repeatable {
+                    next_positional += 1;
+                }
+                seen_operand = true;
+                options_ended = self.settings.posix;
+            } else if self.settings.strict {
+                return Err(self.unknown_argument(arg));
+            } else {
+                trace!(debug, "#{} {:?}: not an argument, added to extra", idx, arg);
+                self.push_extra(arg, &os_args[idx])?;
+                seen_operand = true;
+                // POSIX operands end options.
+                options_ended = self.settings.posix;
             }
         }
 
-        self.args.iter().for_each(|(_, arg)| {
-            if arg.required && !arg.set {
-                println!("Didn't find \"{}\"\n", arg.name);
-                self.help_exit();
-            }
-        });
+        self.assign_positionals(&pending, &args, &os_args)?;
+        self.apply_env()?;
+        self.apply_implications()?;
 
-        self
+        let short_circuit = self.declared_args()
+            .into_iter()
+            .find(|arg| arg.set && arg.short_circuit.is_some())
+            .and_then(|arg| arg.short_circuit.clone());
+        if let Some(callback) = short_circuit {
+            callback(self);
+            return self.info_printed(ParseOutcome::ShortCircuited, 0);
+        }
+
+        self.check_constraints()?;
+        self.run_validators()?;
+        for check in &self.checks {
+            check(self).map_err(|e| Error::new(ErrorKind::Custom, &e))?;
+        }
+
+        let missing: Vec<String> = self.declared_args()
+            .into_iter()
+            .filter(|arg| arg.required && !arg.set)
+            .map(|arg| if arg.positional {format!("<{}>", arg.metavar())} else {arg.display_name()})
+            .collect();
+        match missing.len() {
+            0 => {},
+            1 => return Err(Error::new(ErrorKind::MissingRequired, &format!("Missing required argument: {}", missing[0]))),
+            _ => return Err(Error::new(ErrorKind::MissingRequired,
+                &format!("Missing required arguments: {}", missing.join(", ")))),
+        }
+
+        if let Some(arg) = self.declared_args().into_iter().find(|arg| arg.set && arg.values.len() < arg.min_values) {
+            return Err(Error::new(ErrorKind::MissingValue,
+                &format!("\"{}\" takes at least {} values", arg.display_name(), arg.min_values)));
+        }
+
+        for hook in &self.after_parse {
+            hook(self).map_err(|e| Error::new(ErrorKind::Custom, &e))?;
+        }
+
+        Ok(self)
+    }
+
+    /// Prints `message` the way parsing errors are (colored as ParserSettings::color says,
+    /// followed by ArgParser::render_usage) and exits with ParserSettings::exit_code, for errors found
+    /// by the program after parsing.
+    pub fn error(&self, message: &str) -> ! {
+        self.error_exit(message)
+    }
+
+    /// Gets the name of the program.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the text of the `name` help topic (ArgParser::topic).
+    pub fn get_topic(&self, name: &str) -> Option<&str> {
+        self.topics.get(name).map(|text| &**text)
+    }
+
+    /// Writes every argument's value after parsing and where it came from (ValueSource) to
+    /// `out`, to see why the program behaves as it does. A `--dump-config` flag can call it
+    /// from Arg::short_circuit.
+    pub fn dump(&self, out: &mut impl Write, format: DumpFormat) -> std::io::Result<()> {
+        let args: Vec<&Arg> = self.sorted_args().into_iter().filter(|arg| arg.default != ArgType::Unknown).collect();
+        let values: Vec<(&Arg, Vec<String>, bool)> = args.into_iter()
+            .map(|arg| match arg.current() {
+                ArgType::Flag(boolean) | ArgType::Word(WordType::Boolean(boolean)) => (arg, vec!(boolean.to_string()), true),
+                ArgType::Option_(val) | ArgType::Raw(val) | ArgType::Word(WordType::String_(val)) if arg.values.is_empty() =>
+                    (arg, vec!(val.clone()), false),
+                _ => (arg, arg.values.clone(), false),
+            })
+            .collect();
+
+        match format {
+            DumpFormat::Text => {
+                for (arg, values, boolean) in values {
+                    let value = match values.as_slice() {
+                        [value] if boolean => value.clone(),
+                        [value] => format!("{:?}", value),
+                        values => format!("{:?}", values),
+                    };
+                    writeln!(out, "{} = {} ({})", arg.display_name(), value, arg.source)?;
+                }
+            },
+            DumpFormat::Json => {
+                let fields: Vec<String> = values.into_iter()
+                    .map(|(arg, values, boolean)| {
+                        let value = match values.as_slice() {
+                            [value] if boolean => value.clone(),
+                            [value] => json_string(value),
+                            values => format!("[{}]", values.iter().map(|value| json_string(value)).collect::<Vec<_>>().join(",")),
+                        };
+                        let mut field = format!("{}:{{\"value\":{},\"source\":{}", json_string(&arg.name), value, json_string(&arg.source.to_string()));
+                        if !arg.meta.is_empty() {
+                            let meta: Vec<String> = arg.meta.iter()
+                                .map(|(key, value)| format!("{}:{}", json_string(key), json_string(value)))
+                                .collect();
+                            field.push_str(&format!(",\"meta\":{{{}}}", meta.join(",")));
+                        }
+                        field.push('}');
+                        field
+                    })
+                    .collect();
+                writeln!(out, "{{{}}}", fields.join(","))?;
+            },
+        }
+        Ok(())
+    }
+
+    /// Gets what the last parse ended with, which is only something other than
+    /// ParseOutcome::Parsed with ParserSettings::return_on_info (parsing exits otherwise).
+    pub fn outcome(&self) -> ParseOutcome {
+        self.outcome
+    }
+
+    /// Gets the subcommand found when parsing (if any).
+    pub fn get_subcommand(&self) -> Option<&ArgParser> {
+        self.subcommands.get(self.subcommand.as_ref()?)
+    }
+
+    /// Names of the subcommands that can be given, with the first line of their info.
+    pub (crate) fn subcommand_list(&self) -> Vec<(&str, &str)> {
+        self.subcommands.iter()
+            .map(|(name, sub)| (name.as_str(), sub.info.lines().next().unwrap_or("")))
+            .collect()
+    }
+
+    /// Gets the name of the subcommand found when parsing (if any).
+    pub fn get_subcommand_name(&self) -> Option<&str> {
+        self.subcommand.as_deref()
     }
 
     /// Gets an option argument's output by name.
     pub fn get_option(&self, name: &str) -> Option<String> {
         if let Some(arg) = self.args.get(name) {
-            if let ArgType::Option_(string) = arg.clone().typ {
+            if let ArgType::Option_(string) | ArgType::Raw(string) = arg.current().clone() {
                 return Some(string);
             }
         }
         None
     }
 
+    /// Gets every time the argument was matched when parsing, in order, with the exact tokens
+    /// that matched it (`["--color=auto"]`, `["-c", "auto"]`).
+    pub fn get_occurrences(&self, name: &str) -> Option<Vec<Occurrence>> {
+        Some(self.args.get(name)?.occurrences.clone())
+    }
+
+    /// Gets the arguments passed after `--` (which are also in ArgParser::extra).
+    pub fn get_trailing(&self) -> &[String] {
+        self.trailing_start.map_or(&[], |start| &self.extra[start..])
+    }
+
+    /// Adds the arguments passed after `--` to `command` as they were passed (invalid UTF-8
+    /// included), each as one argument so nothing is split again, for wrapping other programs.
+    pub fn forward<'a>(&self, command: &'a mut Command) -> &'a mut Command {
+        let trailing = self.trailing_start.map_or(&[][..], |start| &self.extra_os[start..]);
+        command.args(trailing)
+    }
+
+    /// Adds every value of the `name` argument (such as an Arg::repeatable word) to `command`
+    /// as they were passed, each as one argument.
+    pub fn forward_values<'a>(&self, name: &str, command: &'a mut Command) -> &'a mut Command {
+        command.args(self.get_os_values(name).unwrap_or_default())
+    }
+
+    /// Gets whether the argument was given when parsing (or implied by one that was), rather
+    /// than left at its default.
+    pub fn is_present(&self, name: &str) -> bool {
+        self.is_set(name)
+    }
+
+    /// Gets every registered Arg in the order the help dialog lists them (ParserSettings::sort_help),
+    /// for rendering help or docs yourself.
+    pub fn get_args(&self) -> Vec<&Arg> {
+        self.sorted_args()
+    }
+
+    /// Gets the Arg registered as `name`, to look at how it was set up and parsed.
+    pub fn get_arg(&self, name: &str) -> Option<&Arg> {
+        self.args.get(name)
+    }
+
+    /// Gets the names of the Arg::required arguments that weren't given, which is still known
+    /// after ArgParser::try_parse fails (to prompt for only those, for example).
+    pub fn missing_required(&self) -> Vec<&str> {
+        self.declared_args()
+            .into_iter()
+            .filter(|arg| arg.required && !arg.set)
+            .map(|arg| arg.name.as_str())
+            .collect()
+    }
+
+    /// Gets an option argument's output by name, only if it was given when parsing.
+    pub fn get_option_if_set(&self, name: &str) -> Option<String> {
+        self.get_option(name).filter(|_| self.is_set(name))
+    }
+
+    /// Gets an option argument's output by name as it was passed, which can be invalid UTF-8.
+    pub fn get_os_option(&self, name: &str) -> Option<OsString> {
+        self.get_os_values(name)?.into_iter().next()
+    }
+
+    /// Gets every value of an option (or WordType::String_ word) argument by name as they were
+    /// passed, in order.
+    /// This is the default value if the option wasn't passed.
+    pub fn get_os_values(&self, name: &str) -> Option<Vec<OsString>> {
+        let arg = self.args.get(name)?;
+        if let ArgType::Option_(string) | ArgType::Raw(string) | ArgType::Word(WordType::String_(string)) = arg.current() {
+            if arg.set {
+                return Some(arg.os_values.clone());
+            }
+            return Some(vec!(OsString::from(string)));
+        }
+        None
+    }
+
+    /// Gets an option argument's output by name as a PathBuf.
+    /// This is the canonicalized path if the argument was set with Arg::canonicalize.
+    pub fn get_path(&self, name: &str) -> Option<PathBuf> {
+        if let Some(arg) = self.args.get(name) {
+            if let Some(path) = arg.paths.first() {
+                return Some(path.clone());
+            }
+        }
+        self.get_os_option(name).map(PathBuf::from)
+    }
+
+    /// Gets every value of an option (or WordType::String_ word) argument by name as PathBufs,
+    /// in order, without going through lossy Strings.
+    /// These are the canonicalized paths if the argument was set with Arg::canonicalize.
+    pub fn get_paths(&self, name: &str) -> Option<Vec<PathBuf>> {
+        if let Some(arg) = self.args.get(name).filter(|arg| !arg.paths.is_empty()) {
+            return Some(arg.paths.clone());
+        }
+        Some(self.get_os_values(name)?.into_iter().map(PathBuf::from).collect())
+    }
+
+    /// Gets an option or string word argument's output by name converted to `T`.
+    pub fn get_typed<T: FromArgValue>(&self, name: &str) -> Option<T> {
+        let val = match self.args.get(name)?.current().clone() {
+            ArgType::Option_(string) | ArgType::Raw(string) => string,
+            ArgType::Word(WordType::String_(string)) => string,
+            _ => return None,
+        };
+        T::from_arg_value(&val).ok()
+    }
+
+    /// Gets an option argument's output by name as a signed integer.
+    /// Accepts `0x`, `0o` and `0b` prefixes and `_` separators.
+    pub fn get_int(&self, name: &str) -> Option<i64> {
+        self.get_option(name).and_then(|val| value::parse_int(&val).ok())
+    }
+
+    /// Gets an option argument's output by name as an unsigned integer.
+    /// Accepts `0x`, `0o` and `0b` prefixes and `_` separators.
+    pub fn get_uint(&self, name: &str) -> Option<u64> {
+        self.get_option(name).and_then(|val| value::parse_uint(&val).ok())
+    }
+
+    /// Gets an option argument's output by name as a boolean.
+    /// Accepts yes/no, on/off, true/false and 1/0 (case-insensitive).
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        self.get_option(name).and_then(|val| value::parse_bool(&val).ok())
+    }
+
+    /// Gets an option argument's output by name as a ratio in [0, 1].
+    /// Accepts `75%`, `0.75` and `3/4`.
+    pub fn get_percent(&self, name: &str) -> Option<f64> {
+        self.get_option(name).and_then(|val| value::parse_percent(&val).ok())
+    }
+
+    /// Gets an option argument's output by name as an (r, g, b) color.
+    /// Accepts `#RRGGBB`, `#RGB` and common color names.
+    pub fn get_color(&self, name: &str) -> Option<(u8, u8, u8)> {
+        self.get_option(name).and_then(|val| value::parse_color(&val).ok())
+    }
+
+    /// Gets an option argument's output by name as a date.
+    #[cfg(feature = "chrono")]
+    pub fn get_date(&self, name: &str) -> Option<chrono::NaiveDate> {
+        self.get_option(name).and_then(|val| value::parse_date(&val).ok())
+    }
+
+    /// Gets an option argument's output by name as a UTC datetime.
+    #[cfg(feature = "chrono")]
+    pub fn get_datetime(&self, name: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.get_option(name).and_then(|val| value::parse_datetime(&val).ok())
+    }
+
+    /// Gets an option argument's output by name as a URL.
+    #[cfg(feature = "url")]
+    pub fn get_url(&self, name: &str) -> Option<url::Url> {
+        self.get_option(name).and_then(|val| value::parse_url(&val).ok())
+    }
+
+    /// Gets every value of an option (or WordType::String_ word) argument by name, in order.
+    /// This is the default value if the option wasn't passed.
+    pub fn get_values(&self, name: &str) -> Option<Vec<String>> {
+        let arg = self.args.get(name)?;
+        if let ArgType::Option_(string) | ArgType::Raw(string) | ArgType::Word(WordType::String_(string)) = arg.current() {
+            if arg.set {
+                return Some(arg.values.clone());
+            }
+            return Some(vec!(string.clone()));
+        }
+        None
+    }
+
+    /// Gets every value of an option argument by name converted to `T`, in order.
+    pub fn get_typed_values<T: FromArgValue>(&self, name: &str) -> Option<Vec<T>> {
+        self.get_values(name)?
+            .iter()
+            .map(|val| T::from_arg_value(val).ok())
+            .collect()
+    }
+
     /// Gets a flag argument's output by name.
     pub fn get_flag(&self, name: &str) -> Option<bool> {
         if let Some(arg) = self.args.get(name) {
-            if let ArgType::Flag(boolean) = arg.typ {
+            if let ArgType::Flag(boolean) = *arg.current() {
                 return Some(boolean);
             }
         }
         None
     }
 
+    /// Gets a flag argument's output by name, None inside if it wasn't given (on the command
+    /// line, with Arg::env or by Arg::implies), to tell `--flag=false` (or `+f` with
+    /// Arg::plus_toggle) apart from the default and keep values from a config file.
+    pub fn get_flag_tristate(&self, name: &str) -> Option<Option<bool>> {
+        let boolean = self.get_flag(name)?;
+        Some(Some(boolean).filter(|_| self.is_set(name)))
+    }
+
     /// Gets a word argument's output by name.
     pub fn get_word(&self, name: &str) -> Option<WordType> {
         if let Some(arg) = self.args.get(name) {
-            if let ArgType::Word(wt) = arg.clone().typ {
+            if let ArgType::Word(wt) = arg.current().clone() {
                 return Some(wt);
             }
         }
@@ -160,17 +1026,46 @@ impl ArgParser {
     }
 
     /// Creates a new ArgParser with `name` &str.
-    pub fn new(name: &str) -> Self {
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        let name = name.into();
         let mut s = Self {
-            name: String::from(name),
-            author: String::new(),
-            version: String::new(),
-            copyright: String::new(),
-            info: String::new(),
-            usage: format!("{} [flags] [options]", name),
+            usage: None,
+            name,
+            examples: Vec::new(),
+            parent_path: None,
+            name_from_argv0: false,
+            author: Cow::Borrowed(""),
+            version: Cow::Borrowed(""),
+            copyright: Cow::Borrowed(""),
+            license: Cow::Borrowed(""),
+            homepage: Cow::Borrowed(""),
+            bug_report_url: None,
+            output: None,
+            info: Cow::Borrowed(""),
             args: BTreeMap::new(),
+            groups: BTreeMap::new(),
             extra: Vec::new(),
-            require_args: false,
+            extra_os: Vec::new(),
+            unknown_flags: Vec::new(),
+            forwarded: Vec::new(),
+            trailing_start: None,
+            settings: ParserSettings::default(),
+            inherit_settings: true,
+            own_settings: Vec::new(),
+            subcommands: BTreeMap::new(),
+            subcommand: None,
+            outcome: ParseOutcome::Parsed,
+            before_parse: Vec::new(),
+            after_parse: Vec::new(),
+            checks: Vec::new(),
+            errors: Vec::new(),
+            renamed: BTreeMap::new(),
+            topics: BTreeMap::new(),
+            build_info: BTreeMap::new(),
+            max_term_width: None,
+            max_extras: None,
+            section_order: Vec::new(),
+            section_titles: BTreeMap::new(),
         };
 
         s.args(vec!(
@@ -186,117 +1081,1182 @@ impl ArgParser {
         s
     }
 
-    /// Prints the help dialog.
-    pub fn print_help(&self) {
-        println!("{} {}\n{}\n{}\n{}", self.name, self.version, self.author, self.info, self.copyright);
-        println!("\nUsage:\n\t{}", self.usage);
+    /// Creates a new ArgParser named after the file stem of `argv[0]` when parsed with
+    /// ArgParser::parse/ArgParser::try_parse, so it follows renames and symlinks.
+    ///
+    /// ArgParser::name overrides it.
+    pub fn from_argv0() -> Self {
+        let mut s = Self::new("");
+        s.name_from_argv0 = true;
+        s
+    }
 
-        let flags: Vec<&Arg> = self.args
-            .iter()
-            .filter(|(_, arg)| if let ArgType::Flag(_) = arg.typ {true} else {false})
-            .map(|(_, arg)| arg)
-            .collect();
+    /// Renders just the usage line and a pointer to --help, which is printed after parsing
+    /// errors instead of the whole help dialog.
+    pub fn render_usage(&self) -> String {
+        format!("Usage: {}\n\nFor more information try '--help'", self.usage_line())
+    }
 
-        let options: Vec<&Arg> = self.args
-            .iter()
-            .filter(|(_, arg)| if let ArgType::Option_(_) = arg.typ {true} else {false})
-            .map(|(_, arg)| arg)
-            .collect();
+    /// Prints the help dialog (one line per argument, shown by -h).
+    pub fn print_help(&self) {
+        self.page(&self.get_help().to_string());
+    }
 
-        let words: Vec<&Arg> = self.args
-            .iter()
-            .filter(|(_, arg)| if let ArgType::Word(_) = arg.typ {true} else {false})
-            .map(|(_, arg)| arg)
-            .collect();
+    /// Prints the expanded help dialog with Arg::long_help descriptions (shown by --help).
+    pub fn print_long_help(&self) {
+        self.page(&self.get_long_help().to_string());
+    }
 
-        if !flags.is_empty() {
-            println!("\nFlags:");
-            flags.iter().for_each(|flag| {
-                println!("\t-{}, --{}\t{}", flag.short, flag.name, flag.help);
-            });
-        }
+    /// Prints the expanded help dialog including Arg::advanced arguments (shown by --help-all
+    /// and -hh).
+    pub fn print_help_all(&self) {
+        self.page(&self.get_help_all().to_string());
+    }
 
-        if !options.is_empty() {
-            println!("\nOptions:");
-            options.iter().for_each(|opt| {
-                println!("\t-{}, --{}\t{}", opt.short, opt.name, opt.help);
-            });
-        }
+    /// Generates a completion script for `shell`, offering Arg::possible_values after options.
+    pub fn completions(&self, shell: Shell) -> String {
+        completions::generate(self, shell)
+    }
 
-        if !words.is_empty() {
-            println!("\nWords:");
-            words.iter().for_each(|opt| {
-                println!("\t{}\t{}", opt.name, opt.help);
-            });
-        }
+    /// Generates a markdown reference of every argument, using Arg::long_help over Arg::help.
+    pub fn markdown(&self) -> String {
+        self.get_help_all().to_markdown()
     }
 
-    /// Sets the name of the program.
-    pub fn name(&mut self, name: &str) -> &mut Self {
-        self.name = String::from(name);
-        self
+    /// Generates a man page (roff) of every argument, using Arg::long_help over Arg::help.
+    pub fn man_page(&self) -> String {
+        self.get_help_all().to_man()
     }
 
-    /// Sets the name of the author of the program.
-    pub fn author(&mut self, author: &str) -> &mut Self {
-        self.author = String::from(author);
-        self
+    /// Gets what the expanded help dialog (ArgParser::print_long_help) shows.
+    pub fn get_long_help(&self) -> Help {
+        Help { expanded: true, ..self.get_help() }
+    }
+
+    /// Gets what the expanded help dialog with Arg::advanced arguments (--help-all) shows.
+    pub fn get_help_all(&self) -> Help {
+        Help { expanded: true, ..self.help_model(true) }
+    }
+
+    /// Gets what the help dialog shows, for rendering it yourself.
+    pub fn get_help(&self) -> Help {
+        self.help_model(false)
+    }
+
+    fn help_model(&self, advanced: bool) -> Help {
+        let mut order: Vec<&str> = self.section_order.iter().map(String::as_str).collect();
+        order.extend(HELP_SECTIONS.iter().filter(|section| !self.section_order.iter().any(|name| name == *section)));
+
+        let mut sections: Vec<HelpSection> = Vec::new();
+        for section in order {
+            let entries: Vec<HelpEntry> = if section == "Commands" {
+                self.subcommand_list().into_iter()
+                    .map(|(name, about)| HelpEntry { name: String::from(name), help: String::from(about), ..HelpEntry::default() })
+                    .collect()
+            } else {
+                self.sorted_args()
+                    .into_iter()
+                    .filter(|arg| advanced || !arg.advanced)
+                    .filter(|arg| help_section(arg) == Some(section))
+                    .map(help_entry)
+                    .collect()
+            };
+            if entries.is_empty() {
+                continue;
+            }
+
+            // Sections given the same title are shown as one.
+            let title = self.section_titles.get(section).map_or(section, String::as_str);
+            match sections.iter_mut().find(|existing| existing.title == title) {
+                Some(existing) => existing.entries.extend(entries),
+                None => sections.push(HelpSection { title: String::from(title), entries }),
+            }
+        }
+
+        if !self.topics.is_empty() {
+            let entries = self.topics.keys()
+                .map(|topic| HelpEntry { name: topic.clone(), ..HelpEntry::default() })
+                .collect();
+            sections.push(HelpSection { title: String::from("Topics (help <topic>)"), entries });
+        }
+
+        Help {
+            name: self.name.to_string(),
+            version: self.version.to_string(),
+            author: self.author.to_string(),
+            info: self.interpolate(&self.info),
+            copyright: self.interpolate(&self.copyright),
+            license: self.license.to_string(),
+            homepage: self.homepage.to_string(),
+            bug_report_url: self.bug_report_url.as_deref().map(String::from),
+            usage: self.usage_line(),
+            sections,
+            examples: self.examples.iter().map(|example| self.interpolate(example)).collect(),
+            expanded: false,
+            width: self.term_width(),
+        }
+    }
+
+    /// Columns to wrap the help dialog at: `$COLUMNS` (or 80), at most ArgParser::max_term_width.
+    fn term_width(&self) -> usize {
+        let columns = env::var("COLUMNS").ok()
+            .and_then(|columns| columns.parse().ok())
+            .filter(|columns| *columns > 0)
+            .unwrap_or(80);
+        self.max_term_width.map_or(columns, |max| columns.min(max))
+    }
+
+    /// Replaces `{name}` (the command, `prog sub` for subcommands), `{version}` and `{author}`
+    /// in `text`, so help text stays right when the name comes from argv[0].
+    fn interpolate(&self, text: &str) -> String {
+        text.replace("{name}", &self.command_path())
+            .replace("{version}", &self.version)
+            .replace("{author}", &self.author)
+    }
+
+    /// The usage, followed by the exclusive (`[--json | --yaml]`) and required (`(--file | --stdin)`)
+    /// groups and the required (`<INPUT>`) and optional (`[OUTPUT]`) positionals if it wasn't set
+    /// with ArgParser::usage.
+    fn usage_line(&self) -> String {
+        if let Some(usage) = &self.usage {
+            return self.interpolate(usage);
+        }
+        let mut usage = format!("{} [flags] [options]", self.command_path());
+
+        for group in self.groups.values().filter(|group| group.required || !group.multiple) {
+            let spellings: Vec<String> = group.args.iter()
+                .filter_map(|name| self.args.get(name))
+                .map(|arg| match &arg.default {
+                    ArgType::Word(_) => arg.name.clone(),
+                    ArgType::Option_(_) | ArgType::Raw(_) => format!("--{} <{}>", arg.name, arg.metavar()),
+                    _ => format!("--{}", arg.name),
+                })
+                .collect();
+            if spellings.is_empty() {
+                continue;
+            }
+
+            let (open, close) = if group.required {('(', ')')} else {('[', ']')};
+            usage.push_str(&format!(" {}{}{}", open, spellings.join(" | "), close));
+        }
+
+        for positional in self.sorted_positionals() {
+            let name = positional.metavar();
+            let dots = if positional.repeatable || positional.last {"..."} else {""};
+            if positional.last {
+                usage.push_str(" [--]");
+            }
+            if positional.required {
+                usage.push_str(&format!(" <{}>{}", name, dots));
+            } else {
+                usage.push_str(&format!(" [{}]{}", name, dots));
+            }
+        }
+        usage
+    }
+
+    /// Sets the name of the program.
+    pub fn name(&mut self, name: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.name = name.into();
+        self.name_from_argv0 = false;
+        self
+    }
+
+    /// Sets the name of the author of the program.
+    pub fn author(&mut self, author: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.author = author.into();
+        self
     }
 
     /// Sets the version of the program.
-    pub fn version(&mut self, version: &str) -> &mut Self {
-        self.version = String::from(version);
+    pub fn version(&mut self, version: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.version = version.into();
         self
     }
 
     /// Sets the copyright (if any) of the program.
-    pub fn copyright(&mut self, copyright: &str) -> &mut Self {
-        self.copyright = String::from(copyright);
+    pub fn copyright(&mut self, copyright: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.copyright = copyright.into();
+        self
+    }
+
+    /// Sets the license of the program as an SPDX expression (`"MIT OR Apache-2.0"`), shown by
+    /// --version, the man page and the markdown reference.
+    pub fn license(&mut self, license: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.license = license.into();
+        self
+    }
+
+    /// Sets the URL of the program's homepage, shown by --version, the man page and the
+    /// markdown reference.
+    pub fn homepage(&mut self, homepage: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.homepage = homepage.into();
+        self
+    }
+
+    /// Sets where to report bugs, shown as `Report bugs at <url>` after the help dialog and
+    /// ErrorKind::Definition errors (mistakes setting up the parser rather than in the
+    /// arguments), like GNU tools. Subcommands without one use their parent's.
+    pub fn bug_report_url(&mut self, url: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.bug_report_url = Some(url.into());
         self
     }
 
-    /// Sets the info of the program.
-    pub fn info(&mut self, info: &str) -> &mut Self {
-        self.info = String::from(info);
+    /// Adds `key` with `value` (a commit hash, build date...) to what `--version=json` prints.
+    pub fn build_info(&mut self, key: &str, value: &str) -> &mut Self {
+        self.build_info.insert(String::from(key), String::from(value));
+        self
+    }
+
+    /// Gets what `--version=json` prints: the name, version, authors (split on `:` and `,`),
+    /// ArgParser::license and ArgParser::homepage (if set) and ArgParser::build_info as a JSON
+    /// object, for tools checking deployed versions.
+    pub fn version_json(&self) -> String {
+        let authors: Vec<String> = self.author.split([':', ','])
+            .map(str::trim)
+            .filter(|author| !author.is_empty())
+            .map(json_string)
+            .collect();
+        let build: Vec<String> = self.build_info.iter()
+            .map(|(key, value)| format!("{}:{}", json_string(key), json_string(value)))
+            .collect();
+        let mut about = String::new();
+        for (key, value) in [("license", &self.license), ("homepage", &self.homepage)] {
+            if !value.is_empty() {
+                about.push_str(&format!("\"{}\":{},", key, json_string(value)));
+            }
+        }
+        format!("{{\"name\":{},\"version\":{},\"authors\":[{}],{}\"build\":{{{}}}}}",
+            json_string(&self.name), json_string(&self.version), authors.join(","), about, build.join(","))
+    }
+
+    /// Sets the info of the program. `{name}`, `{version}` and `{author}` are replaced when
+    /// it's shown, like in ArgParser::usage and ArgParser::example.
+    pub fn info(&mut self, info: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.info = info.into();
         self
     }
 
     /// Sets the usage of the program.
-    pub fn usage(&mut self, usage: &str) -> &mut Self {
-        self.usage = String::from(usage);
+    pub fn usage(&mut self, usage: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.usage = Some(usage.into());
         self
     }
 
-    /// Gives the parser `args` Vec<&mut Arg>.
-    pub fn args(&mut self, args: Vec<&mut Arg>) -> &mut Self {
-        for arg in args {
-            match arg.typ {
-                ArgType::Unknown => panic!("No Args can have type Unknown!"),
-                _ => {self.args.insert(arg.name.clone(), arg.clone());}
+    /// Sets the most columns the help dialog is wrapped at, however wide `$COLUMNS` says the
+    /// terminal is (so it stays readable on wide terminals and the same in CI logs).
+    pub fn max_term_width(&mut self, width: usize) -> &mut Self {
+        self.max_term_width = Some(width);
+        self
+    }
+
+    /// Sets the most arguments ArgParser::extra can collect (0 for none), failing on the
+    /// first one past it instead of collecting it, for commands taking a fixed number of operands.
+    pub fn max_extras(&mut self, max: usize) -> &mut Self {
+        self.max_extras = Some(max);
+        self
+    }
+
+    /// Lists the help sections named in `order` ("Arguments", "Flags", "Options", "Words" and
+    /// "Commands") first, in that order, followed by the others.
+    pub fn group_help<I, S>(&mut self, order: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.section_order.clear();
+        for section in order.into_iter().map(Into::into) {
+            if !HELP_SECTIONS.contains(&section.as_str()) {
+                self.errors.push(Error::new(ErrorKind::Definition,
+                    &format!("No help section \"{}\" (sections: {})", section, HELP_SECTIONS.join(", "))));
+            } else if !self.section_order.contains(&section) {
+                self.section_order.push(section);
             }
+        }
+        self
+    }
 
+    /// Shows the help section named `section` (see ArgParser::group_help) as `title`, like
+    /// "Commands" for "Words". Sections given the same title are shown as one.
+    pub fn section_title(&mut self, section: &str, title: &str) -> &mut Self {
+        if HELP_SECTIONS.contains(&section) {
+            self.section_titles.insert(String::from(section), String::from(title));
+        } else {
+            self.errors.push(Error::new(ErrorKind::Definition,
+                &format!("No help section \"{}\" (sections: {})", section, HELP_SECTIONS.join(", "))));
         }
         self
     }
 
+    /// Adds an example command (`prog sub --fast input.txt`) listed after the arguments in the
+    /// help dialog.
+    pub fn example(&mut self, example: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.examples.push(example.into());
+        self
+    }
+
+    /// The name prefixed with the parsers it's a subcommand of (`prog sub`), as the default
+    /// usage shows it.
+    pub (crate) fn command_path(&self) -> String {
+        match &self.parent_path {
+            Some(parent) => format!("{} {}", parent, self.name),
+            None => self.name.to_string(),
+        }
+    }
+
+    /// Gives the parser `args`, either built inline (`vec!(Arg::new("x").flag(false))`) or
+    /// owned Args from any iterator.
+    pub fn args<A: Into<Arg>>(&mut self, args: impl IntoIterator<Item = A>) -> &mut Self {
+        for mut arg in args.into_iter().map(Into::into).filter(Arg::on_this_platform) {
+            if let Some(new) = &arg.replaced_by {
+                self.renamed.insert(arg.name.clone(), new.clone());
+                continue;
+            }
+
+            match arg.validate() {
+                Err(e) => self.errors.push(e),
+                Ok(()) => {
+                    // Words and positionals aren't matched by a short, so they don't keep the
+                    // one taken from their name.
+                    if !arg.is_named() {
+                        arg.short = None;
+                    }
+                    arg.index = self.args.get(&arg.name).map_or(self.args.len(), |old| old.index);
+                    self.args.insert(arg.name.clone(), arg);
+                },
+            }
+        }
+        self
+    }
+
+    /// Gives the parser a single `arg` (ArgParser::args).
+    pub fn arg(&mut self, arg: impl Into<Arg>) -> &mut Self {
+        self.args([arg])
+    }
+
+    /// Index of the first --help, -h, --help-all, -hh, --version or -v in `args` that's before
+    /// `--`, a subcommand (whose own help it would be) and the positional that takes the rest
+    /// (Arg::last, ParserSettings::trailing_var_arg), skipping the values options and words take.
+    fn info_request(&self, args: &[String]) -> Option<usize> {
+        let help_all = !self.args.contains_key("help-all");
+        let hyphen_values = self.settings.allow_hyphen_values;
+        let positionals = self.sorted_positionals();
+        let mut next_positional = 0;
+        let mut idx = 0;
+        while let Some(arg) = args.get(idx) {
+            if arg == "--" || self.subcommands.contains_key(arg) {
+                return None;
+            }
+            match arg.as_str() {
+                "--help" | "-h" | "-hh" | "--version" | "-v" => return Some(idx),
+                "--help-all" if help_all => return Some(idx),
+                _ => {},
+            }
+
+            // How many of the arguments after this one it takes as values.
+            let consumed = if let Some(word) = self.args.get(arg).filter(|arg| matches!(arg.default, ArgType::Word(_))) {
+                let takes = |next: &&String| hyphen_values || !next.starts_with('-');
+                match word.default {
+                    ArgType::Word(WordType::String_(_)) if word.repeatable => args[idx + 1..].iter()
+                        .take_while(|next| takes(next) && !self.args.contains_key(*next) && !self.subcommands.contains_key(*next))
+                        .count(),
+                    ArgType::Word(WordType::String_(_)) => args.get(idx + 1).filter(takes).map_or(0, |_| 1),
+                    _ => 0,
+                }
+            } else if let Some(long) = arg.strip_prefix("--") {
+                let (name, value) = match long.split_once('=') {
+                    Some((name, value)) => (name, Some(value)),
+                    None => (long, None),
+                };
+                let name = self.long_name(name).unwrap_or_default();
+                match self.args.get(&name).filter(|arg| arg.is_named() && !matches!(arg.default, ArgType::Flag(_))) {
+                    Some(option) => option_values(option, args, idx, value, hyphen_values)
+                        .map_or(0, |values| values.len() - value.iter().count()),
+                    None => 0,
+                }
+            } else if let Some(shorts) = arg.strip_prefix('-').filter(|shorts| !shorts.is_empty()) {
+                let (shorts, value) = match shorts.split_once('=') {
+                    Some((shorts, value)) => (shorts, Some(value)),
+                    None => (shorts, None),
+                };
+                let mut taken = idx;
+                let last = shorts.chars().count().saturating_sub(1);
+                for (short_idx, ch) in shorts.chars().enumerate() {
+                    let value = value.filter(|_| short_idx == last);
+                    let option = self.args.values()
+                        .find(|arg| arg.short == Some(ch) && arg.is_named() && !matches!(arg.default, ArgType::Flag(_)));
+                    if let Some(values) = option.and_then(|option| option_values(option, args, taken, value, hyphen_values)) {
+                        taken += values.len() - value.iter().count();
+                    }
+                }
+                taken - idx
+            } else {
+                match positionals.get(next_positional) {
+                    Some(positional) if positional.last
+                        || (self.settings.trailing_var_arg && next_positional + 1 == positionals.len()) => return None,
+                    Some(positional) if !positional.repeatable => next_positional += 1,
+                    Some(_) => {},
+                    // POSIX operands end options.
+                    None if self.settings.posix => return None,
+                    None => {},
+                }
+                0
+            };
+            idx += consumed + 1;
+        }
+        None
+    }
+
+    /// Adds `arg` to ArgParser::extra, or fails if there are already ArgParser::max_extras.
+    fn push_extra(&mut self, arg: &str, os_arg: &OsStr) -> Result<(), Error> {
+        if self.max_extras.is_some_and(|max| self.extra.len() >= max) {
+            return Err(Error::new(ErrorKind::UnknownArgument, &format!("Unexpected extra operand '{}'", arg)));
+        }
+        self.extra.push(String::from(arg));
+        self.extra_os.push(os_arg.to_os_string());
+        Ok(())
+    }
+
+    /// Runs the Arg::validator checks of the arguments that were set, in declaration order.
+    fn run_validators(&self) -> Result<(), Error> {
+        for arg in self.declared_args().into_iter().filter(|arg| arg.set) {
+            let values = match *arg.current() {
+                ArgType::Flag(boolean) | ArgType::Word(WordType::Boolean(boolean)) => vec!(boolean.to_string()),
+                _ => arg.values.clone(),
+            };
+            for val in &values {
+                for validator in &arg.validators {
+                    validator(val, self).map_err(|e| arg.invalid_value(val, &e))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that no required positional comes after an optional one (without
+    /// ParserSettings::allow_missing_positional) and that only the last positional is repeatable
+    /// (or Arg::last), since the values couldn't be told apart otherwise. Ran before parsing
+    /// rather than by Arg::positional, once the settings are final.
+    fn validate_positionals(&self) -> Result<(), Error> {
+        let positionals = self.sorted_positionals();
+        for (before, after) in positionals.iter().zip(positionals.iter().skip(1)) {
+            if before.repeatable || before.last {
+                return Err(Error::new(ErrorKind::Definition,
+                    &format!("Positional \"{}\" is {} but isn't the last positional", before.name,
+                        if before.last {"last"} else {"repeatable"})));
+            } else if after.required && !before.required && !self.settings.allow_missing_positional {
+                return Err(Error::new(ErrorKind::Definition,
+                    &format!("Required positional \"{}\" comes after optional positional \"{}\"", after.name, before.name)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Gives the positional values at `pending` to the positionals, skipping optional ones from
+    /// the end when there are too few (`SRC [MIDDLE] DST` with two values gives SRC and DST).
+    fn assign_positionals(&mut self, pending: &[usize], args: &[String], os_args: &[OsString]) -> Result<(), Error> {
+        let positionals: Vec<String> = self.sorted_positionals().iter()
+            .filter(|arg| !arg.last)
+            .map(|arg| arg.name.clone())
+            .collect();
+        let required = positionals.iter().filter(|name| self.args[*name].required).count();
+        let mut optional = pending.len().saturating_sub(required);
+
+        let mut pending = pending.iter().peekable();
+        for name in positionals {
+            let arg = match self.args.get_mut(&name) {
+                Some(arg) => arg,
+                None => continue,
+            };
+            if !arg.required {
+                if optional == 0 {continue}
+                optional -= 1;
+            }
+
+            // Only the last positional is repeatable, so it takes every value left.
+            let take = if arg.repeatable {pending.len()} else {1};
+            for &idx in pending.by_ref().take(take) {
+                arg.set_os_values(&[&args[idx]], &os_args[idx..=idx])?;
+                arg.occurred(idx, &args[idx..=idx]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Gives the Arg::last positional `name` every argument from `idx` on.
+    fn take_rest(&mut self, name: &str, args: &[String], os_args: &[OsString], idx: usize) -> Result<(), Error> {
+        if let Some(arg) = self.args.get_mut(name) {
+            let values: Vec<&str> = args[idx..].iter().map(String::as_str).collect();
+            arg.set_os_values(&values, &os_args[idx..])?;
+            arg.occurred(idx, &args[idx..]);
+        }
+        Ok(())
+    }
+
+    /// Positional arguments in the order they were given to the parser.
+    fn sorted_positionals(&self) -> Vec<&Arg> {
+        self.declared_args().into_iter().filter(|arg| arg.positional).collect()
+    }
+
+    /// Gives the parser `arg`, which has a kind (see ArgBuilder).
+    pub fn typed_arg(&mut self, arg: ArgBuilder<Typed>) -> &mut Self {
+        self.arg(arg.build())
+    }
+
+    /// Sets the order arguments are listed in by the help dialog with `order` SortOrder.
+    pub fn sort_help(&mut self, order: SortOrder) -> &mut Self {
+        self.settings.sort_help = order;
+        self.own_setting("sort_help");
+        self
+    }
+
+    /// Sets every behavior toggle at once with `settings` ParserSettings, except for the ones
+    /// already set with their own builders (ArgParser::sort_help, ArgParser::require_args).
+    pub fn settings(&mut self, mut settings: ParserSettings) -> &mut Self {
+        self.keep_own_settings(&mut settings);
+        self.settings = settings;
+        self.inherit_settings = false;
+        self
+    }
+
+    fn own_setting(&mut self, field: &'static str) {
+        if !self.own_settings.contains(&field) {
+            self.own_settings.push(field);
+        }
+    }
+
+    /// Copies the settings set with their own builders into `settings`.
+    fn keep_own_settings(&self, settings: &mut ParserSettings) {
+        for field in &self.own_settings {
+            match *field {
+                "sort_help" => settings.sort_help = self.settings.sort_help,
+                "require_args" => settings.require_args = self.settings.require_args,
+                _ => {}
+            }
+        }
+    }
+
+    /// Gives the parser `sub` ArgParser as a subcommand, parsed when its name is found.
+    ///
+    /// Subcommands use this parser's settings (except for require_args and the ones set with
+    /// their own builders) unless ArgParser::settings was called on them, and are given this parser's Arg::global arguments.
+    pub fn subcommand(&mut self, mut sub: ArgParser) -> &mut Self {
+        sub.parent_path = Some(self.command_path());
+        self.subcommands.insert(sub.name.to_string(), sub);
+        self
+    }
+
+    /// Runs `hook` with the raw arguments before parsing, letting it change them (e.g. expand
+    /// an alias file). An Err is reported like any other parsing error.
+    pub fn before_parse<F>(&mut self, hook: F) -> &mut Self
+    where
+        F: Fn(&mut Vec<String>) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.before_parse.push(Arc::new(hook));
+        self
+    }
+
+    /// Runs `hook` with the parser after parsing (e.g. to validate the results). An Err is
+    /// reported like any other parsing error.
+    pub fn after_parse<F>(&mut self, hook: F) -> &mut Self
+    where
+        F: Fn(&ArgParser) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.after_parse.push(Arc::new(hook));
+        self
+    }
+
+    /// Runs `check` with the parser once every argument is parsed, before required arguments
+    /// are checked, for rules spanning several arguments ("--start must be before --end"). An
+    /// Err is reported like any other parsing error.
+    pub fn check<F>(&mut self, check: F) -> &mut Self
+    where
+        F: Fn(&ArgParser) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.checks.push(Arc::new(check));
+        self
+    }
+
+    /// Gives the parser `group` ArgGroup.
+    pub fn group(&mut self, group: &mut ArgGroup) -> &mut Self {
+        let mut group = group.clone();
+        group.index = self.groups.get(&group.name).map_or(self.groups.len(), |old| old.index);
+        self.groups.insert(group.name.clone(), group);
+        self
+    }
+
+    /// Adds a `name` help topic with `text` long-form help, shown by `prog help <name>` or
+    /// `--help=<name>` instead of making the help dialog longer.
+    pub fn topic(&mut self, name: &str, text: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.topics.insert(String::from(name), text.into());
+        self
+    }
+
     /// Sets whether or not the program should exit when no arguments are passed.
     pub fn require_args(&mut self, require: bool) -> &mut Self {
-        self.require_args = require;
+        self.settings.require_args = require;
+        self.own_setting("require_args");
         self
     }
 
-    fn help_exit(&self) {
-        self.print_help();
-        process::exit(1);
+    /// Parses `args` with the `name` subcommand, passing down settings and global arguments.
+    fn parse_subcommand(&mut self, name: &str, args: Vec<OsString>) -> Result<(), Error> {
+        let mut sub = match self.subcommands.remove(name) {
+            Some(sub) => sub,
+            None => return Ok(()),
+        };
+
+        if sub.inherit_settings {
+            let mut settings = self.settings.clone();
+            settings.require_args = sub.settings.require_args;
+            sub.keep_own_settings(&mut settings);
+            sub.settings = settings;
+        }
+
+        for arg in self.args.values().filter(|arg| arg.global) {
+            sub.args.insert(arg.name.clone(), arg.clone());
+        }
+        // This parser may have been made a subcommand (or renamed from argv[0]) since.
+        sub.parent_path = Some(self.command_path());
+        sub.max_term_width = sub.max_term_width.or(self.max_term_width);
+        if sub.bug_report_url.is_none() {
+            sub.bug_report_url.clone_from(&self.bug_report_url);
+        }
+        if sub.output.is_none() {
+            sub.output.clone_from(&self.output);
+        }
+
+        let result = sub.try_parse_os_vec(args).map(|_| ());
+
+        // Values of global arguments given to the subcommand.
+        for arg in sub.args.values().filter(|arg| arg.global && arg.set) {
+            self.args.insert(arg.name.clone(), arg.clone());
+        }
+        self.outcome = sub.outcome;
+
+        self.subcommands.insert(String::from(name), sub);
+        self.subcommand = Some(String::from(name));
+        result
+    }
+
+    /// Takes the name from `argv[0]` if made with ArgParser::from_argv0
+    /// (or without a name).
+    pub (crate) fn apply_argv0(&mut self, argv0: &OsStr) {
+        if !self.name_from_argv0 && !self.name.is_empty() {
+            return;
+        }
+
+        let name = match Path::new(argv0).file_stem() {
+            Some(stem) => stem.to_string_lossy().into_owned(),
+            None => return,
+        };
+        self.name = name.into();
+    }
+
+    /// `-name` as the long argument it stands for with ParserSettings::single_dash_long, if any:
+    /// `--name` for `-name[=value]`, or `--name=value` for `-namevalue` (the longest option
+    /// name `-namevalue` starts with).
+    fn single_dash_long(&self, arg: &str) -> Option<String> {
+        let body = arg.strip_prefix('-').filter(|body| !body.starts_with('-') && !body.is_empty())?;
+        let name = body.split('=').next().unwrap_or_default();
+        let long = |arg: &&Arg| !matches!(arg.default, ArgType::Word(_) | ArgType::Unknown);
+
+        if self.args.get(name).filter(long).is_some() {
+            return Some(format!("-{}", arg));
+        }
+
+        // Single chars are left for shorts.
+        if graphemes(body).len() < 2 {
+            return None;
+        }
+
+        self.args.values()
+            .filter(|arg| matches!(arg.default, ArgType::Option_(_) | ArgType::Raw(_)) && body.len() > arg.name.len() && body.starts_with(&arg.name))
+            .max_by_key(|arg| arg.name.len())
+            .map(|arg| format!("--{}={}", arg.name, &body[arg.name.len()..]))
+    }
+
+    /// Sets arguments that weren't passed from their Arg::env variable.
+    fn apply_env(&mut self) -> Result<(), Error> {
+        let debug = self.debugging();
+        for arg in self.args.values_mut().filter(|arg| !arg.set) {
+            let raw = match arg.env.as_ref().and_then(env::var_os) {
+                Some(raw) if !raw.is_empty() => raw,
+                _ => continue,
+            };
+            trace!(debug, "\"{}\" from ${}: {:?}", arg.display_name(), arg.env.as_deref().unwrap_or_default(), raw);
+
+            let val = raw.to_string_lossy().into_owned();
+            match arg.default {
+                ArgType::Option_(_) | ArgType::Raw(_) | ArgType::Word(WordType::String_(_)) => arg.set_os_values(&[&val], &[raw])?,
+                _ => arg.set_implied(&val)?,
+            }
+            arg.source = ValueSource::Environment;
+        }
+        Ok(())
+    }
+
+    /// Sets the values implied by every set argument (Arg::implies) on arguments that weren't
+    /// set, until nothing more is implied.
+    fn apply_implications(&mut self) -> Result<(), Error> {
+        let debug = self.debugging();
+        loop {
+            let implied: Vec<(String, String)> = self.args.values()
+                .filter(|arg| arg.set)
+                .flat_map(|arg| arg.implies.clone())
+                .filter(|(name, _)| !self.args.get(name).is_some_and(|arg| arg.set))
+                .collect();
+
+            if implied.is_empty() {return Ok(())}
+
+            for (name, val) in implied {
+                let arg = match self.args.get_mut(&name) {
+                    Some(arg) => arg,
+                    None => return Err(Error::new(ErrorKind::Definition,
+                        &format!("Implied argument \"{}\" doesn't exist", name))),
+                };
+
+                // Already implied by another argument.
+                if arg.set {continue}
+                trace!(debug, "implied: \"{}\" = {:?}", arg.display_name(), val);
+
+                arg.set_implied(&val)?;
+                arg.source = ValueSource::Implied;
+            }
+        }
+    }
+
+    /// The error for `arg` not being an argument, suggesting the word for `--word` and the
+    /// flag or option for `name` if they're what was meant.
+    fn unknown_argument(&self, arg: &str) -> Error {
+        let suggestion = match arg.strip_prefix("--") {
+            Some(name) => self.args.get(name).filter(|arg| matches!(arg.default, ArgType::Word(_))).map(|arg| arg.name.clone()),
+            None => self.args.get(arg).filter(|arg| arg.is_named()).map(Arg::display_name),
+        };
+        match suggestion {
+            Some(suggestion) => Error::new(ErrorKind::UnknownArgument,
+                &format!("Unexpected argument: \"{}\" (did you mean \"{}\"?)", arg, suggestion)),
+            None => unexpected(arg),
+        }
+    }
+
+    /// Prints `text`, through $PAGER (or `less -R`) with ParserSettings::pager if stdout is a
+    /// terminal it doesn't fit in.
+    fn page(&self, text: &str) {
+        if self.output.is_some() {
+            return self.print_out(text);
+        }
+        let paging = match env::var("RARGSXD_PAGER") {
+            Ok(val) => !val.is_empty() && val != "0",
+            Err(_) => self.settings.pager,
+        };
+        let height = tty_height()
+            .or_else(|| env::var("LINES").ok().and_then(|lines| lines.parse().ok()))
+            .unwrap_or(24);
+        if paging && std::io::stdout().is_terminal() && text.lines().count() > height {
+            let pager = env::var("PAGER").ok().filter(|pager| !pager.trim().is_empty())
+                .unwrap_or_else(|| String::from("less -R"));
+            let mut words = pager.split_whitespace();
+            if let Some(program) = words.next() {
+                let child = Command::new(program).args(words).stdin(Stdio::piped()).spawn();
+                if let Ok(mut child) = child {
+                    if let Some(mut stdin) = child.stdin.take() {
+                        // The pager quitting early closes the pipe, which isn't an error here.
+                        let _ = stdin.write_all(text.as_bytes());
+                    }
+                    if child.wait().is_ok() {
+                        return;
+                    }
+                }
+            }
+        }
+        print!("{}", text);
+    }
+
+    /// Whether or not to trace parsing (ParserSettings::debug or RARGSXD_DEBUG=1).
+    fn debugging(&self) -> bool {
+        self.settings.debug || env::var("RARGSXD_DEBUG").is_ok_and(|val| !val.is_empty() && val != "0")
+    }
+
+    /// Arguments in the order they were given to the parser, which checks go in.
+    fn declared_args(&self) -> Vec<&Arg> {
+        let mut args: Vec<&Arg> = self.args.values().collect();
+        args.sort_by_key(|arg| arg.index);
+        args
+    }
+
+    /// Arguments in the order set by ParserSettings::sort_help.
+    pub (crate) fn sorted_args(&self) -> Vec<&Arg> {
+        let mut args: Vec<&Arg> = self.args.values().collect();
+        if self.settings.sort_help == SortOrder::Declaration {
+            args.sort_by_key(|arg| arg.index);
+        }
+        args
+    }
+
+    /// Names of the arguments `name` refers to: the argument itself or its group's arguments.
+    fn resolve(&self, name: &str) -> Result<Vec<String>, Error> {
+        if let Some(group) = self.groups.get(name) {
+            return Ok(group.args.clone());
+        }
+
+        if !self.args.contains_key(name) {
+            return Err(Error::new(ErrorKind::Definition, &format!("Argument or group \"{}\" doesn't exist", name)));
+        }
+        Ok(vec!(String::from(name)))
+    }
+
+    /// How `name` (an argument or group) is shown in errors.
+    fn describe(&self, name: &str) -> String {
+        let names: Vec<String> = self.resolve(name)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|arg| self.args.get(arg))
+            .map(|arg| arg.display_name())
+            .collect();
+
+        if self.groups.contains_key(name) {
+            format!("\"{}\" ({})", name, names.join(", "))
+        } else {
+            format!("\"{}\"", names.join(", "))
+        }
+    }
+
+    fn is_set(&self, name: &str) -> bool {
+        self.args.get(name).is_some_and(|arg| arg.set)
+    }
+
+    /// Checks Arg::requires, Arg::conflicts and ArgGroup constraints.
+    fn check_constraints(&self) -> Result<(), Error> {
+        for arg in self.declared_args().into_iter().filter(|arg| arg.set) {
+            for name in &arg.requires {
+                if !self.resolve(name)?.iter().any(|name| self.is_set(name)) {
+                    return Err(Error::new(ErrorKind::MissingRequired,
+                        &format!("\"{}\" requires {}", arg.display_name(), self.describe(name))));
+                }
+            }
+
+            for name in &arg.conflicts {
+                let conflicting = self.resolve(name)?
+                    .into_iter()
+                    .find_map(|other| self.args.get(&other).filter(|other| other.name != arg.name && other.set));
+
+                if let Some(other) = conflicting {
+                    return Err(Error::new(ErrorKind::Conflict,
+                        &format!("\"{}\" can't be used with \"{}\"", arg.display_name(), other.display_name())));
+                }
+            }
+        }
+
+        let mut groups: Vec<&ArgGroup> = self.groups.values().collect();
+        groups.sort_by_key(|group| group.index);
+        for group in groups {
+            let set = self.resolve(&group.name)?.iter().filter(|name| self.is_set(name)).count();
+            if group.required && set == 0 {
+                return Err(Error::new(ErrorKind::MissingRequired, &format!("Didn't find one of {}", self.describe(&group.name))));
+            } else if !group.multiple && set > 1 {
+                return Err(Error::new(ErrorKind::Conflict, &format!("Only one of {} can be used", self.describe(&group.name))));
+            }
+        }
+        Ok(())
     }
 
-    fn version_exit(&self) {
-        println!("{} {}", self.name, self.version);
-        process::exit(1);
+    /// Exits with `code` once help or version info is printed, or returns from parsing with
+    /// `outcome` if ParserSettings::return_on_info is set.
+    fn info_printed(&mut self, outcome: ParseOutcome, code: i32) -> Result<&mut Self, Error> {
+        if !self.settings.return_on_info && self.output.is_none() {
+            process::exit(code);
+        }
+        self.outcome = outcome;
+        Ok(self)
     }
 
-    fn unexpected(&self, arg: &str) {
-        eprintln!("Unexpected argument: \"{}\"", arg);
-        self.help_exit();
+    /// Prints the `topic` help topic, or returns the error if there isn't one.
+    fn print_topic(&self, topic: &str) -> Result<(), Error> {
+        if let Some(text) = self.topics.get(topic) {
+            self.print_out(&format!("{}\n", text));
+            return Ok(());
+        }
+
+        let topics: Vec<&str> = self.topics.keys().map(String::as_str).collect();
+        Err(Error::new(ErrorKind::UnknownArgument,
+            &format!("No help topic \"{}\" (topics: {})", topic, topics.join(", "))))
+    }
+
+    fn print_version(&self) {
+        let mut version = format!("{} {}\n", self.name, self.version);
+        if !self.license.is_empty() {
+            version.push_str(&format!("License: {}\n", self.license));
+        }
+        if !self.homepage.is_empty() {
+            version.push_str(&format!("Homepage: {}\n", self.homepage));
+        }
+        self.print_out(&version);
+    }
+
+    /// Prints `text` to stdout, or adds it to the output captured by ArgParser::capture_output.
+    fn print_out(&self, text: &str) {
+        match &self.output {
+            Some(output) => output.lock().unwrap_or_else(|e| e.into_inner()).push_str(text),
+            None => print!("{}", text),
+        }
+    }
+
+    /// Prints `text` to stderr, or adds it to the output captured by ArgParser::capture_output.
+    fn print_err(&self, text: &str) {
+        match &self.output {
+            Some(output) => output.lock().unwrap_or_else(|e| e.into_inner()).push_str(text),
+            None => eprint!("{}", text),
+        }
+    }
+
+    /// Captures what parsing prints (help and version info, warnings) instead of printing it, and returns
+    /// from parsing after printing it instead of exiting, for the testing module.
+    pub (crate) fn capture_output(&mut self) -> Arc<Mutex<String>> {
+        let output = Arc::new(Mutex::new(String::new()));
+        self.output = Some(output.clone());
+        output
+    }
+
+    fn error_exit(&self, error: &str) -> ! {
+        let color = match self.settings.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => std::io::stderr().is_terminal(),
+        };
+
+        if color {
+            eprintln!("\x1b[1;31m{}\x1b[0m", error);
+        } else {
+            eprintln!("{}", error);
+        }
+
+        // Usage of the subcommand the error happened in.
+        let mut parser = self;
+        while let Some(sub) = parser.get_subcommand() {
+            parser = sub;
+        }
+        eprintln!("\n{}", parser.render_usage());
+        process::exit(self.settings.exit_code);
+    }
+
+    /// Resolves `name` to the full name of a long argument, allowing unambiguous prefixes if
+    /// ParserSettings::abbreviations is set and `_` for `-` (or the other way around) if
+    /// ParserSettings::underscores_as_hyphens is. Returns `name` as is if nothing matches.
+    fn long_name(&self, name: &str) -> Result<String, Error> {
+        if name.is_empty() || self.args.contains_key(name) {
+            return Ok(String::from(name));
+        }
+
+        let fold = |name: &str| if self.settings.underscores_as_hyphens {name.replace('_', "-")} else {String::from(name)};
+        let folded = fold(name);
+        if let Some(arg) = self.args.values().find(|arg| arg.is_named() && fold(&arg.name) == folded) {
+            return Ok(arg.name.clone());
+        }
+        if !self.settings.abbreviations {
+            return Ok(String::from(name));
+        }
+
+        let matches: Vec<&Arg> = self.args.values()
+            .filter(|arg| arg.is_named() && fold(&arg.name).starts_with(&folded))
+            .collect();
+
+        match matches.len() {
+            0 => Ok(String::from(name)),
+            1 => Ok(matches[0].name.clone()),
+            _ => {
+                let names: Vec<String> = matches.iter().map(|arg| arg.display_name()).collect();
+                Err(Error::new(ErrorKind::Ambiguous, &format!("Ambiguous argument \"--{}\" could be {}", name, names.join(", "))))
+            },
+        }
+    }
+}
+
+/// Rows of the terminal stdout is on (the TIOCGWINSZ ioctl), None if it isn't one.
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "ios",
+    target_os = "freebsd", target_os = "openbsd", target_os = "netbsd", target_os = "dragonfly"))]
+fn tty_height() -> Option<usize> {
+    use std::os::raw::{c_int, c_ulong};
+
+    #[repr(C)]
+    struct Winsize {
+        rows: u16,
+        columns: u16,
+        x_pixels: u16,
+        y_pixels: u16,
+    }
+    extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+    #[cfg(all(any(target_os = "linux", target_os = "android"),
+        not(any(target_arch = "mips", target_arch = "mips64", target_arch = "powerpc", target_arch = "powerpc64", target_arch = "sparc64"))))]
+    const TIOCGWINSZ: c_ulong = 0x5413;
+    #[cfg(not(all(any(target_os = "linux", target_os = "android"),
+        not(any(target_arch = "mips", target_arch = "mips64", target_arch = "powerpc", target_arch = "powerpc64", target_arch = "sparc64")))))]
+    const TIOCGWINSZ: c_ulong = 0x40087468;
+
+    let mut size = Winsize { rows: 0, columns: 0, x_pixels: 0, y_pixels: 0 };
+    // SAFETY: TIOCGWINSZ only writes a winsize struct to the pointer, which points to one.
+    let result = unsafe { ioctl(1, TIOCGWINSZ, &mut size as *mut Winsize) };
+    (result == 0 && size.rows > 0).then_some(usize::from(size.rows))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "ios",
+    target_os = "freebsd", target_os = "openbsd", target_os = "netbsd", target_os = "dragonfly")))]
+fn tty_height() -> Option<usize> {
+    None
+}
+
+/// Whether or not `s` is one or more ASCII digits (`-9` for Arg::digit_shorts).
+fn is_digits(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|ch| ch.is_ascii_digit())
+}
+
+/// The part of `--name=value` after the first `=`, keeping invalid UTF-8 on unix.
+fn after_equals(arg: &OsStr) -> OsString {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let bytes = arg.as_bytes();
+        let start = bytes.iter().position(|b| *b == b'=').map_or(bytes.len(), |idx| idx + 1);
+        OsStr::from_bytes(&bytes[start..]).to_os_string()
+    }
+
+    #[cfg(not(unix))]
+    {
+        let arg = arg.to_string_lossy();
+        OsString::from(arg.split_once('=').map_or("", |(_, value)| value))
+    }
+}
+
+/// Splits `s` into chars with their following combining marks, so `-a\u{308}` is one (unknown)
+/// short argument rather than `-a` followed by a lone combining mark.
+fn graphemes(s: &str) -> Vec<String> {
+    let mut graphemes: Vec<String> = Vec::new();
+    for ch in s.chars() {
+        match graphemes.last_mut() {
+            Some(last) if is_combining(ch) => last.push(ch),
+            _ => graphemes.push(ch.to_string()),
+        }
+    }
+    graphemes
+}
+
+/// `text` as a JSON string literal.
+fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            ch if ch.is_control() => json.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => json.push(ch),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn unexpected(arg: &str) -> Error {
+    Error::new(ErrorKind::UnknownArgument, &format!("Unexpected argument: \"{}\"", arg))
+}
+
+/// Collects the values for option `arg` at `idx`, starting with `value` (--name=value) if given.
+/// Returns None if the values passed don't fit Arg::nargs.
+fn option_values<'a>(arg: &Arg, args: &'a [String], idx: usize, value: Option<&'a str>, hyphen_values: bool) -> Option<Vec<&'a str>> {
+    let (min, max) = arg.nargs.range();
+    let mut values: Vec<&str> = value.into_iter().collect();
+    let raw = matches!(arg.default, ArgType::Raw(_));
+    let hyphen_values = hyphen_values || raw;
+    for next in args.iter().skip(idx + 1).take(max.saturating_sub(values.len())) {
+        if !hyphen_values && next.starts_with('-') && !(arg.stdin && next == "-") {break}
+        // Taking every value stops at the end of options.
+        if !raw && max == usize::MAX && next == "--" {break}
+        values.push(next);
+    }
+
+    if (min..=max).contains(&values.len()) {Some(values)} else {None}
+}
+
+/// Parses `args` with a copy of `definition`, which is left as it is, so one definition can be
+/// shared between threads and parsed any number of times with the same results. The copy is
+/// returned to read the values from.
+///
+/// Like ArgParser::try_parse_vec, --help, --version and Arg::short_circuit arguments exit
+/// unless ParserSettings::return_on_info is set, and Arg::on_match callbacks still run.
+pub fn parse<S: AsRef<OsStr>>(definition: &ArgParser, args: &[S]) -> Result<ArgParser, Error> {
+    let mut parser = definition.clone();
+    parser.reset();
+    parser.try_parse_os_vec(args.iter().map(|arg| arg.as_ref().to_os_string()).collect())?;
+    Ok(parser)
+}
+
+/// Replaces every `@file` argument (with `prefix` as the @) before `--` with the arguments in
+/// the file, split like a shell would (shell_split), expanding files they name in turn.
+fn expand_arg_files(args: Vec<OsString>, prefix: char, depth: usize) -> Result<Vec<OsString>, Error> {
+    let mut expanded = Vec::with_capacity(args.len());
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            expanded.push(arg);
+            expanded.extend(args);
+            break;
+        }
+
+        let path = match arg.to_str().and_then(|arg| arg.strip_prefix(prefix)) {
+            Some(path) if !path.is_empty() => path,
+            _ => {
+                expanded.push(arg);
+                continue;
+            },
+        };
+        if depth == ARG_FILE_DEPTH {
+            return Err(Error::new(ErrorKind::InvalidValue,
+                &format!("Argument files are nested more than {} deep at \"{}\"", ARG_FILE_DEPTH, path)));
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::new(ErrorKind::InvalidValue, &format!("Couldn't read argument file \"{}\": {}", path, e)))?;
+        let file_args = shell_split(&contents, Quoting::native())
+            .map_err(|e| Error::new(ErrorKind::InvalidValue, &format!("Couldn't split argument file \"{}\": {}", path, e)))?;
+        expanded.extend(expand_arg_files(file_args.into_iter().map(OsString::from).collect(), prefix, depth + 1)?);
+    }
+    Ok(expanded)
+}
+
+/// Title of the help dialog section `arg` is listed in.
+fn help_section(arg: &Arg) -> Option<&'static str> {
+    match &arg.default {
+        ArgType::Option_(_) | ArgType::Raw(_) if arg.positional => Some("Arguments"),
+        ArgType::Flag(_) => Some("Flags"),
+        ArgType::Option_(_) | ArgType::Raw(_) => Some("Options"),
+        ArgType::Word(_) => Some("Words"),
+        ArgType::Unknown => None,
+    }
+}
+
+/// How `arg` is shown in the help dialog.
+fn help_entry(arg: &Arg) -> HelpEntry {
+    let word = !arg.is_named();
+    let (metavar, default) = match &arg.default {
+        ArgType::Option_(default) | ArgType::Raw(default) | ArgType::Word(WordType::String_(default)) =>
+            (Some(arg.metavar()), Some(default.clone()).filter(|default| !default.is_empty() && !arg.hide_default)),
+        _ => (None, None),
+    };
+
+    HelpEntry {
+        name: arg.name.clone(),
+        short: arg.short.filter(|_| !word),
+        long: Some(arg.name.clone()).filter(|_| !word),
+        metavar,
+        help: arg.help.to_string(),
+        long_help: arg.long_help.as_ref().map(|help| help.to_string()),
+        default,
+        env: arg.env.clone(),
+        possible_values: arg.possible_values.clone(),
     }
 }
@@ -0,0 +1,225 @@
+/// Quoting rules used by shell_quote and shell_join.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Quoting {
+    /// POSIX sh (and bash, zsh...): single quotes, with `'` written as `'\''`.
+    Posix,
+    /// cmd.exe and CommandLineToArgvW: double quotes, with `"` and the backslashes before it
+    /// escaped by backslashes. Arguments with `"`, `%` or `!` have every cmd.exe metacharacter
+    /// (quotes included) escaped with `^` too, since cmd.exe would end the quotes early or expand
+    /// variables inside them.
+    Cmd,
+}
+
+impl Quoting {
+    /// The rules of the shell the program was built for: Quoting::Cmd on Windows,
+    /// Quoting::Posix elsewhere.
+    pub fn native() -> Self {
+        if cfg!(windows) {Self::Cmd} else {Self::Posix}
+    }
+}
+
+/// Quotes `arg` so the shell `quoting` is for gives it back as one argument, leaving it as is
+/// if it doesn't need quoting.
+pub fn shell_quote(arg: &str, quoting: Quoting) -> String {
+    match quoting {
+        Quoting::Posix => posix(arg),
+        Quoting::Cmd => cmd(arg),
+    }
+}
+
+/// Quotes every argument in `args` with shell_quote and joins them with spaces, for printing
+/// a command ("dry run: would run ...").
+pub fn shell_join<I, S>(args: I, quoting: Quoting) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    args.into_iter()
+        .map(|arg| shell_quote(arg.as_ref(), quoting))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn posix(arg: &str) -> String {
+    let safe = |ch: char| ch.is_ascii_alphanumeric() || "_-+=@%:,./".contains(ch);
+    if !arg.is_empty() && arg.chars().all(safe) {
+        return String::from(arg);
+    }
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+fn cmd(arg: &str) -> String {
+    let special = |ch: char| ch.is_whitespace() || "\"&|<>^()%!".contains(ch);
+    if !arg.is_empty() && !arg.chars().any(special) {
+        return String::from(arg);
+    }
+
+    let quoted = argv_quote(arg);
+    if !arg.contains(['"', '%', '!']) {
+        return quoted;
+    }
+    // cmd.exe sees no quotes at all then, so every metacharacter is outside them.
+    let mut escaped = String::with_capacity(quoted.len() * 2);
+    for ch in quoted.chars() {
+        if "\"&|<>^()%!".contains(ch) {
+            escaped.push('^');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Quotes `arg` for CommandLineToArgvW.
+fn argv_quote(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for ch in arg.chars() {
+        match ch {
+            '\\' => backslashes += 1,
+            '"' => {
+                // Backslashes before a quote are escapes, so double them and escape the quote.
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                backslashes = 0;
+            },
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                backslashes = 0;
+            },
+        }
+        if ch != '\\' {
+            quoted.push(ch);
+        }
+    }
+    // Backslashes before the closing quote too.
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// Splits `line` into arguments the way the shell `quoting` is for would, the reverse of
+/// shell_join. Fails on an unterminated quote.
+///
+/// Quoting::Cmd takes `^` escapes outside quotes like cmd.exe does first, then splits the rest
+/// like CommandLineToArgvW (with `""` inside quotes as a literal quote).
+pub fn shell_split(line: &str, quoting: Quoting) -> Result<Vec<String>, String> {
+    match quoting {
+        Quoting::Posix => split_posix(line),
+        Quoting::Cmd => split_cmd(line),
+    }
+}
+
+fn split_posix(line: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut arg: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            ch if ch.is_whitespace() => args.extend(arg.take()),
+            '\\' => {
+                let arg = arg.get_or_insert_with(String::new);
+                match chars.next() {
+                    Some('\n') => {},
+                    Some(next) => arg.push(next),
+                    None => arg.push('\\'),
+                }
+            },
+            '\'' => {
+                let arg = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(next) => arg.push(next),
+                        None => return Err(String::from("unterminated ' quote")),
+                    }
+                }
+            },
+            '"' => {
+                let arg = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(next) if "$`\"\\".contains(next) => arg.push(next),
+                            Some('\n') => {},
+                            Some(next) => {arg.push('\\');arg.push(next);},
+                            None => return Err(String::from("unterminated \" quote")),
+                        },
+                        Some(next) => arg.push(next),
+                        None => return Err(String::from("unterminated \" quote")),
+                    }
+                }
+            },
+            ch => arg.get_or_insert_with(String::new).push(ch),
+        }
+    }
+    args.extend(arg);
+    Ok(args)
+}
+
+fn split_cmd(line: &str) -> Result<Vec<String>, String> {
+    // cmd.exe removes `^` escapes outside its quotes (where a `^"` isn't a quote).
+    let mut unescaped = String::with_capacity(line.len());
+    let mut quoted = false;
+    let mut chars = line.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '^' if !quoted => match chars.next() {
+                Some(next) => unescaped.push(next),
+                None => return Err(String::from("^ at the end of the line")),
+            },
+            '"' => {
+                quoted = !quoted;
+                unescaped.push(ch);
+            },
+            ch => unescaped.push(ch),
+        }
+    }
+
+    let mut args = Vec::new();
+    let mut arg: Option<String> = None;
+    let mut quoted = false;
+    let mut backslashes = 0;
+    let mut chars = unescaped.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch.is_whitespace() && !quoted {
+            if let Some(arg) = arg.as_mut() {
+                arg.push_str(&"\\".repeat(backslashes));
+            }
+            backslashes = 0;
+            args.extend(arg.take());
+            continue;
+        }
+
+        let arg = arg.get_or_insert_with(String::new);
+        match ch {
+            '\\' => backslashes += 1,
+            '"' => {
+                // 2n backslashes before a quote are n backslashes, 2n + 1 escape the quote too.
+                arg.push_str(&"\\".repeat(backslashes / 2));
+                if backslashes % 2 == 1 {
+                    arg.push('"');
+                } else if quoted && chars.peek() == Some(&'"') {
+                    chars.next();
+                    arg.push('"');
+                } else {
+                    quoted = !quoted;
+                }
+                backslashes = 0;
+            },
+            _ => {
+                arg.push_str(&"\\".repeat(backslashes));
+                backslashes = 0;
+                arg.push(ch);
+            },
+        }
+    }
+
+    if quoted {
+        return Err(String::from("unterminated \" quote"));
+    }
+    if let Some(arg) = arg.as_mut() {
+        arg.push_str(&"\\".repeat(backslashes));
+    }
+    args.extend(arg);
+    Ok(args)
+}
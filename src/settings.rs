@@ -0,0 +1,126 @@
+/// When to color error output.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ColorChoice {
+    /// Color if stderr is a terminal.
+    Auto,
+    /// Always color.
+    Always,
+    /// Never color.
+    Never,
+}
+
+/// Order arguments are listed in by the help dialog.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SortOrder {
+    /// Sorted by name.
+    Alphabetical,
+    /// In the order they were given to the parser.
+    Declaration,
+}
+
+/// Behavior toggles for an ArgParser, set all at once with ArgParser::settings.
+///
+/// ```rust
+/// use rargsxd::*;
+///
+/// let mut parser = ArgParser::new("program_lol");
+/// parser.settings(ParserSettings {
+///     abbreviations: true,
+///     strict: true,
+///     ..ParserSettings::default()
+/// });
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParserSettings {
+    /// Prints help and exits if no args are passed when parsing.
+    pub require_args: bool,
+    /// Exits on arguments that aren't registered instead of collecting them in ArgParser::extra.
+    pub strict: bool,
+    /// Lets long options be given by an unambiguous prefix (`--verb` for `--verbose`).
+    pub abbreviations: bool,
+    /// Lets option values start with `-` (`--offset -5`).
+    pub allow_hyphen_values: bool,
+    /// When to color error output.
+    pub color: ColorChoice,
+    /// Exit code used when parsing fails.
+    pub exit_code: i32,
+    /// Order arguments are listed in by the help dialog.
+    pub sort_help: SortOrder,
+    /// Traces how every argument is parsed to stderr. Also enabled by `RARGSXD_DEBUG=1`.
+    pub debug: bool,
+    /// Picks the subcommand named after `argv[0]`'s file stem (busybox-style) when parsing
+    /// std::env::args_os(), so symlinks to one binary act as its subcommands.
+    pub multicall: bool,
+    /// Lets long arguments be given with a single dash (`-name foo`, `-Xmx2g` for a "Xmx" option)
+    /// like find(1) or java, and stops shorts from being combined (`-ab` isn't `-a -b`).
+    pub single_dash_long: bool,
+    /// Follows POSIX utility conventions: options have to come before operands (the first
+    /// operand ends options), long arguments aren't allowed and `-W` is rejected.
+    pub posix: bool,
+    /// Collects `-x`/`--xyz` arguments that aren't registered in ArgParser::unknown_flags
+    /// instead of failing, to forward them elsewhere.
+    pub collect_unknown: bool,
+    /// Gives the last positional every argument left once it's reached, dashes and all, like
+    /// Arg::last (`prog run BIN ARGS...` without `--`).
+    pub trailing_var_arg: bool,
+    /// Lets required positionals come after optional ones (`prog SRC [MIDDLE] DST`), giving the
+    /// optional ones values only when there are enough for the required ones.
+    pub allow_missing_positional: bool,
+    /// Fails on options given after an operand (`prog file.txt --force`) instead of parsing
+    /// them, so options are always given first.
+    pub options_first: bool,
+    /// Shows help longer than the terminal (its height, `$LINES` or 24) through `$PAGER` (or `less -R`)
+    /// when stdout is a terminal, like git. `RARGSXD_PAGER=1` or `=0` overrides it.
+    pub pager: bool,
+    /// Returns from parsing after printing help or version info (or running an
+    /// Arg::short_circuit callback), with ArgParser::outcome saying which, instead of exiting
+    /// (for GUIs and daemons that parse commands themselves).
+    pub return_on_info: bool,
+    /// Replaces arguments starting with this character (`@args.txt` for Some('@'), like javac
+    /// or gcc) with the arguments in the file, which can name more files up to 10 deep.
+    /// None (the default) leaves them as they are.
+    pub arg_file_prefix: Option<char>,
+    /// Handles --help and --version where they're given, so errors in the arguments before
+    /// them are reported first. Otherwise they're handled before anything else is parsed (and
+    /// required arguments are never checked).
+    pub info_in_order: bool,
+    /// Moves `--xyz` arguments that aren't registered (with the value after them, if it doesn't
+    /// start with `-`) to ArgParser::forwarded instead of failing, so older versions of a program
+    /// still run scripts written for newer ones. See ParserSettings::warn_forwarded.
+    pub forward_unknown: bool,
+    /// Warns about every argument moved to ArgParser::forwarded on stderr (the default). Turn
+    /// it off to warn about them another way.
+    pub warn_forwarded: bool,
+    /// Matches long arguments with `_` and `-` treated the same, so `--dry_run` is `--dry-run`.
+    /// The help dialog still shows them as they were named.
+    pub underscores_as_hyphens: bool,
+}
+
+impl Default for ParserSettings {
+    fn default() -> Self {
+        Self {
+            require_args: false,
+            strict: false,
+            abbreviations: false,
+            allow_hyphen_values: false,
+            color: ColorChoice::Never,
+            exit_code: 1,
+            sort_help: SortOrder::Alphabetical,
+            debug: false,
+            multicall: false,
+            single_dash_long: false,
+            posix: false,
+            collect_unknown: false,
+            trailing_var_arg: false,
+            allow_missing_positional: false,
+            options_first: false,
+            pager: false,
+            return_on_info: false,
+            arg_file_prefix: None,
+            info_in_order: false,
+            forward_unknown: false,
+            warn_forwarded: true,
+            underscores_as_hyphens: false,
+        }
+    }
+}
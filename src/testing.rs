@@ -0,0 +1,87 @@
+//! Helpers for testing a program's arguments in-process, without running it.
+//!
+//! They parse a copy of the parser (like rargsxd::parse) that captures help and version info
+//! instead of printing it and exiting, so `--help` and Arg::short_circuit arguments can be
+//! tested too.
+//!
+//! ```rust
+//! use rargsxd::*;
+//!
+//! let mut parser = ArgParser::new("program_lol");
+//! parser.version("0.1.0").args(vec!(Arg::new("verbose").flag(false)));
+//!
+//! let parsed = parse_ok!(parser, ["--verbose"]);
+//! assert!(parsed.get_flag("verbose").unwrap());
+//! parse_err!(parser, ["--bogus"], ErrorKind::UnknownArgument);
+//! assert_output!(parser, ["--version"], "program_lol 0.1.0\n");
+//! ```
+
+use super::error::{Error, ErrorKind};
+use super::parser::{self, ArgParser};
+
+use std::ffi::OsStr;
+
+/// Parses `args` with a copy of `definition`, returning the copy or panicking with the error.
+pub fn parse_ok<S: AsRef<OsStr>>(definition: &ArgParser, args: &[S]) -> ArgParser {
+    match run(definition, args).0 {
+        Ok(parser) => parser,
+        Err(e) => panic!("parsing {:?} failed: {}", lossy(args), e),
+    }
+}
+
+/// Parses `args` with a copy of `definition`, returning the error or panicking if there isn't
+/// one of `kind`.
+pub fn parse_err<S: AsRef<OsStr>>(definition: &ArgParser, args: &[S], kind: ErrorKind) -> Error {
+    match run(definition, args).0 {
+        Ok(_) => panic!("parsing {:?} succeeded, expected a {:?} error", lossy(args), kind),
+        Err(e) if e.kind() != kind => panic!("parsing {:?} failed with a {:?} error, expected {:?}: {}",
+            lossy(args), e.kind(), kind, e),
+        Err(e) => e,
+    }
+}
+
+/// Gets what parsing `args` with a copy of `definition` prints (help dialogs, help topics,
+/// version info and warnings), empty if it prints nothing.
+pub fn output<S: AsRef<OsStr>>(definition: &ArgParser, args: &[S]) -> String {
+    run(definition, args).1
+}
+
+/// Parses `args` with a copy of `definition` capturing what's printed.
+fn run<S: AsRef<OsStr>>(definition: &ArgParser, args: &[S]) -> (Result<ArgParser, Error>, String) {
+    let mut capturing = definition.clone();
+    let output = capturing.capture_output();
+    let result = parser::parse(&capturing, args);
+    let output = output.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    (result, output)
+}
+
+fn lossy<S: AsRef<OsStr>>(args: &[S]) -> Vec<String> {
+    args.iter().map(|arg| arg.as_ref().to_string_lossy().into_owned()).collect()
+}
+
+/// Parses the args with a copy of the parser, evaluating to the copy and panicking if parsing
+/// fails (rargsxd::testing::parse_ok).
+#[macro_export]
+macro_rules! parse_ok {
+    ($parser:expr, $args:expr) => {
+        $crate::testing::parse_ok(&$parser, &$args)
+    };
+}
+
+/// Parses the args with a copy of the parser, evaluating to the error and panicking if there
+/// isn't one of the ErrorKind (rargsxd::testing::parse_err).
+#[macro_export]
+macro_rules! parse_err {
+    ($parser:expr, $args:expr, $kind:expr) => {
+        $crate::testing::parse_err(&$parser, &$args, $kind)
+    };
+}
+
+/// Asserts that parsing the args with a copy of the parser prints the expected text
+/// (rargsxd::testing::output), like `--help` or `--version`.
+#[macro_export]
+macro_rules! assert_output {
+    ($parser:expr, $args:expr, $expected:expr) => {
+        assert_eq!($crate::testing::output(&$parser, &$args), $expected)
+    };
+}
@@ -0,0 +1,377 @@
+use std::env;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// How the value of an option (or string word) is interpreted when parsing.
+#[derive(Clone, PartialEq)]
+pub enum ValueType {
+    /// Any string. This is the default.
+    String_,
+    /// A filesystem path.
+    Path,
+    /// A signed 64 bit integer. Accepts `0x`, `0o` and `0b` prefixes and `_` separators.
+    Integer,
+    /// An unsigned 64 bit integer. Accepts `0x`, `0o` and `0b` prefixes and `_` separators.
+    Unsigned,
+    /// A boolean. Accepts yes/no, on/off, true/false and 1/0 (case-insensitive).
+    Boolean,
+    /// A ratio between 0 and 1. Accepts `75%`, `0.75` and `3/4`.
+    Percent,
+    /// An (r, g, b) color. Accepts `#RRGGBB`, `#RGB` and common color names.
+    Color,
+    /// An ISO-8601 date (`2024-01-01`) or `today`, `yesterday` and `tomorrow`.
+    #[cfg(feature = "chrono")]
+    Date,
+    /// An ISO-8601 datetime (`2024-01-01T00:00:00Z`), date (midnight UTC) or `now`, `today`,
+    /// `yesterday` and `tomorrow`.
+    #[cfg(feature = "chrono")]
+    DateTime,
+    /// An absolute URL (`https://api.example.com/v1`).
+    #[cfg(feature = "url")]
+    Url,
+}
+
+/// Conversion from an argument's value, used by ArgParser::get_typed.
+///
+/// Implemented for every type implementing FromStr, so it only needs implementing by hand for
+/// types that don't.
+pub trait FromArgValue: Sized {
+    /// Converts `val` to Self, returning why it couldn't be converted otherwise.
+    fn from_arg_value(val: &str) -> Result<Self, String>;
+}
+
+impl<T: FromStr> FromArgValue for T where T::Err: Display {
+    fn from_arg_value(val: &str) -> Result<Self, String> {
+        val.parse().map_err(|e: T::Err| e.to_string())
+    }
+}
+
+/// Checks that `val` can be interpreted as `vt`.
+pub (crate) fn validate(vt: &ValueType, val: &str) -> Result<(), String> {
+    match vt {
+        ValueType::Integer => parse_int(val).map(|_| ()),
+        ValueType::Unsigned => parse_uint(val).map(|_| ()),
+        ValueType::Boolean => parse_bool(val).map(|_| ()),
+        ValueType::Percent => parse_percent(val).map(|_| ()),
+        ValueType::Color => parse_color(val).map(|_| ()),
+        #[cfg(feature = "chrono")]
+        ValueType::Date => parse_date(val).map(|_| ()),
+        #[cfg(feature = "chrono")]
+        ValueType::DateTime => parse_datetime(val).map(|_| ()),
+        #[cfg(feature = "url")]
+        ValueType::Url => parse_url(val).map(|_| ()),
+        _ => Ok(()),
+    }
+}
+
+/// Parses a signed integer literal such as `-42`, `0x1F`, `0o755`, `0b1010` or `1_000_000`.
+pub (crate) fn parse_int(val: &str) -> Result<i64, String> {
+    let (negative, digits) = match val.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, val.strip_prefix('+').unwrap_or(val)),
+    };
+
+    let magnitude = parse_magnitude(digits)?;
+    if negative {
+        if magnitude > i64::MAX as u64 + 1 {
+            return Err(String::from("number too small"));
+        }
+        Ok((magnitude as i64).wrapping_neg())
+    } else {
+        if magnitude > i64::MAX as u64 {
+            return Err(String::from("number too large"));
+        }
+        Ok(magnitude as i64)
+    }
+}
+
+/// Parses an unsigned integer literal such as `42`, `0x1F`, `0o755`, `0b1010` or `1_000_000`.
+pub (crate) fn parse_uint(val: &str) -> Result<u64, String> {
+    if val.starts_with('-') {
+        return Err(String::from("number can't be negative"));
+    }
+    parse_magnitude(val.strip_prefix('+').unwrap_or(val))
+}
+
+/// Parses a boolean spelled as yes/no, on/off, true/false or 1/0 (case-insensitive).
+pub (crate) fn parse_bool(val: &str) -> Result<bool, String> {
+    match val.to_ascii_lowercase().as_str() {
+        "yes" | "on" | "true" | "1" => Ok(true),
+        "no" | "off" | "false" | "0" => Ok(false),
+        _ => Err(String::from("expected one of yes, no, on, off, true, false, 1, 0")),
+    }
+}
+
+/// Parses a percentage (`75%`), decimal (`0.75`) or fraction (`3/4`) into a ratio in [0, 1].
+pub (crate) fn parse_percent(val: &str) -> Result<f64, String> {
+    let number = |s: &str| s.trim().parse::<f64>().map_err(|_| String::from("expected a ratio like 75%, 0.75 or 3/4"));
+
+    let ratio = if let Some(percent) = val.strip_suffix('%') {
+        number(percent)? / 100.0
+    } else if let Some((numerator, denominator)) = val.split_once('/') {
+        let denominator = number(denominator)?;
+        if denominator == 0.0 {
+            return Err(String::from("division by zero"));
+        }
+        number(numerator)? / denominator
+    } else {
+        number(val)?
+    };
+
+    if !(0.0..=1.0).contains(&ratio) {
+        return Err(String::from("must be between 0% and 100%"));
+    }
+    Ok(ratio)
+}
+
+/// Parses a `#RRGGBB` or `#RGB` hex code, or a common color name, into an (r, g, b) tuple.
+pub (crate) fn parse_color(val: &str) -> Result<(u8, u8, u8), String> {
+    let error = || String::from("expected a color like #ff8800, #f80 or orange");
+
+    if let Some(hex) = val.strip_prefix('#') {
+        if !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+        let channel = |s: &str| u8::from_str_radix(s, 16).map_err(|_| error());
+        return match hex.len() {
+            6 => Ok((channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?)),
+            // #f80 is shorthand for #ff8800
+            3 => Ok((channel(&hex[0..1])? * 17, channel(&hex[1..2])? * 17, channel(&hex[2..3])? * 17)),
+            _ => Err(error()),
+        };
+    }
+
+    match val.to_ascii_lowercase().as_str() {
+        "black" => Ok((0, 0, 0)),
+        "white" => Ok((255, 255, 255)),
+        "red" => Ok((255, 0, 0)),
+        "green" => Ok((0, 128, 0)),
+        "lime" => Ok((0, 255, 0)),
+        "blue" => Ok((0, 0, 255)),
+        "yellow" => Ok((255, 255, 0)),
+        "cyan" | "aqua" => Ok((0, 255, 255)),
+        "magenta" | "fuchsia" => Ok((255, 0, 255)),
+        "gray" | "grey" => Ok((128, 128, 128)),
+        "silver" => Ok((192, 192, 192)),
+        "maroon" => Ok((128, 0, 0)),
+        "olive" => Ok((128, 128, 0)),
+        "navy" => Ok((0, 0, 128)),
+        "purple" => Ok((128, 0, 128)),
+        "teal" => Ok((0, 128, 128)),
+        "orange" => Ok((255, 165, 0)),
+        "pink" => Ok((255, 192, 203)),
+        "brown" => Ok((165, 42, 42)),
+        _ => Err(error()),
+    }
+}
+
+/// Parses an ISO-8601 date or a relative day (`today`, `yesterday`, `tomorrow`).
+#[cfg(feature = "chrono")]
+pub (crate) fn parse_date(val: &str) -> Result<chrono::NaiveDate, String> {
+    use chrono::{Duration, NaiveDate, Utc};
+
+    let today = Utc::now().date_naive();
+    match val.to_ascii_lowercase().as_str() {
+        "today" => return Ok(today),
+        "yesterday" => return Ok(today - Duration::days(1)),
+        "tomorrow" => return Ok(today + Duration::days(1)),
+        _ => {},
+    }
+
+    NaiveDate::parse_from_str(val, "%Y-%m-%d")
+        .or_else(|_| parse_datetime(val).map(|dt| dt.date_naive()))
+        .map_err(|_| String::from("expected a date like 2024-01-01"))
+}
+
+/// Parses an ISO-8601 datetime, a date (midnight UTC) or a relative time (`now`, `today`,
+/// `yesterday`, `tomorrow`). Datetimes without an offset are taken as UTC.
+#[cfg(feature = "chrono")]
+pub (crate) fn parse_datetime(val: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+    if val.eq_ignore_ascii_case("now") {
+        return Ok(Utc::now());
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(val) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+
+    for format in &["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(val, format) {
+            return Ok(dt.and_utc());
+        }
+    }
+
+    let relative = matches!(val.to_ascii_lowercase().as_str(), "today" | "yesterday" | "tomorrow");
+    if relative || NaiveDate::parse_from_str(val, "%Y-%m-%d").is_ok() {
+        if let Some(dt) = parse_date(val)?.and_hms_opt(0, 0, 0) {
+            return Ok(dt.and_utc());
+        }
+    }
+
+    Err(String::from("expected a datetime like 2024-01-01T00:00:00Z"))
+}
+
+/// Parses an absolute URL.
+#[cfg(feature = "url")]
+pub (crate) fn parse_url(val: &str) -> Result<url::Url, String> {
+    url::Url::parse(val).map_err(|e| e.to_string())
+}
+
+fn parse_magnitude(val: &str) -> Result<u64, String> {
+    let lower = val.to_ascii_lowercase();
+    let (radix, digits) = if let Some(digits) = lower.strip_prefix("0x") {
+        (16, digits)
+    } else if let Some(digits) = lower.strip_prefix("0o") {
+        (8, digits)
+    } else if let Some(digits) = lower.strip_prefix("0b") {
+        (2, digits)
+    } else {
+        (10, lower.as_str())
+    };
+
+    if digits.is_empty() || digits.starts_with('_') || digits.ends_with('_') || digits.contains("__") {
+        return Err(String::from("not a number"));
+    }
+
+    let mut n: u64 = 0;
+    for ch in digits.chars().filter(|ch| *ch != '_') {
+        let digit = ch.to_digit(radix).ok_or_else(|| String::from("not a number"))?;
+        n = n.checked_mul(radix as u64)
+            .and_then(|n| n.checked_add(digit as u64))
+            .ok_or_else(|| String::from("number too large"))?;
+    }
+    Ok(n)
+}
+
+/// Resolves `path` to an absolute, canonicalized PathBuf.
+pub (crate) fn canonicalize(path: &Path) -> Result<PathBuf, String> {
+    std::fs::canonicalize(path)
+        .map_err(|e| format!("Couldn't resolve path \"{}\": {}", path.display(), e))
+}
+
+/// Paths matching the glob `pattern` (`src/**/*.rs`), sorted. Empty if nothing matches.
+#[cfg(feature = "glob")]
+pub (crate) fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>, String> {
+    let paths = glob::glob(pattern).map_err(|e| e.to_string())?;
+    Ok(paths.filter_map(Result::ok).collect())
+}
+
+/// Expands `$VAR` and `${VAR}` references in `val` using the process environment.
+/// Unset variables expand to an empty string, like in a shell.
+pub (crate) fn expand_env(val: &str) -> String {
+    let mut out = String::new();
+    let mut chars = val.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '$' {
+            out.push(ch);
+            continue;
+        }
+
+        let mut name = String::new();
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            let mut closed = false;
+            for ch in chars.by_ref() {
+                if ch == '}' {
+                    closed = true;
+                    break;
+                }
+                name.push(ch);
+            }
+
+            if !closed {
+                // Unterminated ${, leave it as is.
+                out.push_str("${");
+                out.push_str(&name);
+                continue;
+            }
+        } else {
+            while let Some(&ch) = chars.peek() {
+                if !(ch.is_ascii_alphanumeric() || ch == '_') {break}
+                name.push(ch);
+                chars.next();
+            }
+
+            if name.is_empty() {
+                out.push('$');
+                continue;
+            }
+        }
+
+        out.push_str(&env::var(&name).unwrap_or_default());
+    }
+
+    out
+}
+
+/// Reads a value from stdin (Arg::stdin), without the trailing newline. The OsString keeps
+/// the bytes as they were where the platform allows it.
+pub (crate) fn read_stdin() -> Result<(String, OsString), String> {
+    let mut bytes = Vec::new();
+    std::io::stdin().read_to_end(&mut bytes).map_err(|e| format!("couldn't read stdin: {}", e))?;
+    Ok(stdin_value(bytes))
+}
+
+/// The value read from stdin as `bytes`.
+pub (crate) fn stdin_value(mut bytes: Vec<u8>) -> (String, OsString) {
+    if bytes.ends_with(b"\n") {
+        bytes.pop();
+        if bytes.ends_with(b"\r") {
+            bytes.pop();
+        }
+    }
+
+    let lossy = String::from_utf8_lossy(&bytes).into_owned();
+    #[cfg(unix)]
+    let raw = std::os::unix::ffi::OsStringExt::from_vec(bytes);
+    #[cfg(not(unix))]
+    let raw = OsString::from(&lossy);
+    (lossy, raw)
+}
+
+/// Expands a leading `~` (the current user's home) or `~user` (`user`'s home) in `path`.
+/// Paths that don't start with a tilde, or name an unknown user, are returned unchanged.
+pub (crate) fn expand_tilde(path: &str) -> String {
+    let rest = match path.strip_prefix('~') {
+        Some(rest) => rest,
+        None => return String::from(path),
+    };
+
+    let (user, rest) = match rest.find('/') {
+        Some(idx) => rest.split_at(idx),
+        None => (rest, ""),
+    };
+
+    let home = if user.is_empty() {
+        env::var("HOME").or_else(|_| env::var("USERPROFILE")).ok()
+    } else {
+        user_home(user)
+    };
+
+    match home {
+        Some(home) if home.trim_end_matches('/').is_empty() && rest.is_empty() => String::from("/"),
+        Some(home) => format!("{}{}", home.trim_end_matches('/'), rest),
+        None => String::from(path),
+    }
+}
+
+/// Looks up `user`'s home directory in /etc/passwd.
+#[cfg(unix)]
+fn user_home(user: &str) -> Option<String> {
+    let passwd = std::fs::read_to_string("/etc/passwd").ok()?;
+    passwd.lines()
+        .map(|line| line.split(':').collect::<Vec<_>>())
+        .find(|fields| fields.len() > 5 && fields[0] == user)
+        .map(|fields| String::from(fields[5]))
+}
+
+#[cfg(not(unix))]
+fn user_home(_user: &str) -> Option<String> {
+    None
+}